@@ -1,7 +1,8 @@
 use serde_json::Value;
 use std::error::Error;
 use std::fs::File;
-use std::io::Read;
+use std::io::{self, Read, Write};
+use std::thread;
 
 #[derive(Clone)]
 pub struct Automaton {
@@ -133,7 +134,8 @@ impl Automaton {
         pattern
     }
 
-    fn get_number_of_alive_nighbours(&self, (x, y): Position) -> i32 {
+    /// Alive cells among the eight cells around `(x, y)`.
+    pub fn get_number_of_alive_neighbours(&self, (x, y): Position) -> i32 {
         let matrix = &self.matrix;
 
         let left_boundary = x.checked_sub(1);
@@ -184,35 +186,59 @@ impl Automaton {
     }
 
     pub fn iterate(&self) -> Automaton {
-        let matrix = &self.matrix;
-
-        let mut new_matrix = matrix.clone();
+        let new_matrix = (0..self.matrix.len()).map(|i| self.next_row(i)).collect();
 
-        for i in 0..matrix.len() {
-            for j in 0..matrix[i].len() {
-                let cell = matrix[i][j];
+        Automaton {
+            matrix: new_matrix,
+            starting_point: self.starting_point,
+            finishing_point: self.finishing_point,
+            generation: self.generation + 1,
+        }
+    }
 
-                let number_of_alive_neighbours = self.get_number_of_alive_nighbours((i, j));
+    /// The automaton at `generation`, which cannot be before the current one.
+    pub fn advance_to(&self, generation: u32) -> Automaton {
+        if generation < self.generation {
+            panic!(
+                "Cannot go back from generation {} to generation {}",
+                self.generation, generation
+            );
+        }
 
-                new_matrix[i][j] = CellState::Dead;
+        let mut automaton = self.clone();
 
-                match cell {
-                    CellState::Alive => {
-                        if stay_alive_rule(number_of_alive_neighbours) {
-                            new_matrix[i][j] = CellState::Alive
-                        }
-                    }
-                    CellState::Dead => {
-                        if become_alive_rule(number_of_alive_neighbours) {
-                            new_matrix[i][j] = CellState::Alive
-                        }
-                    }
-                    CellState::Start => new_matrix[i][j] = CellState::Start,
-                    CellState::Finish => new_matrix[i][j] = CellState::Finish,
-                }
-            }
+        while automaton.generation < generation {
+            automaton = automaton.iterate();
         }
 
+        automaton
+    }
+
+    /// Same as `iterate`, with the rows split between `threads` threads.
+    pub fn iterate_parallel(&self, threads: usize) -> Automaton {
+        let rows = self.matrix.len();
+        let chunk_size = rows.div_ceil(threads.max(1));
+
+        let new_matrix = thread::scope(|scope| {
+            let handles: Vec<_> = (0..rows)
+                .step_by(chunk_size)
+                .map(|first_row| {
+                    let last_row = (first_row + chunk_size).min(rows);
+
+                    scope.spawn(move || {
+                        (first_row..last_row)
+                            .map(|i| self.next_row(i))
+                            .collect::<Vec<_>>()
+                    })
+                })
+                .collect();
+
+            handles
+                .into_iter()
+                .flat_map(|handle| handle.join().unwrap())
+                .collect()
+        });
+
         Automaton {
             matrix: new_matrix,
             starting_point: self.starting_point,
@@ -221,6 +247,37 @@ impl Automaton {
         }
     }
 
+    fn next_row(&self, i: usize) -> Vec<CellState> {
+        let matrix = &self.matrix;
+
+        let mut new_row = matrix[i].clone();
+
+        for j in 0..matrix[i].len() {
+            let cell = matrix[i][j];
+
+            let number_of_alive_neighbours = self.get_number_of_alive_neighbours((i, j));
+
+            new_row[j] = CellState::Dead;
+
+            match cell {
+                CellState::Alive => {
+                    if stay_alive_rule(number_of_alive_neighbours) {
+                        new_row[j] = CellState::Alive
+                    }
+                }
+                CellState::Dead => {
+                    if become_alive_rule(number_of_alive_neighbours) {
+                        new_row[j] = CellState::Alive
+                    }
+                }
+                CellState::Start => new_row[j] = CellState::Start,
+                CellState::Finish => new_row[j] = CellState::Finish,
+            }
+        }
+
+        new_row
+    }
+
     #[allow(dead_code)]
     pub fn print(&self, current_position: Option<Position>) {
         let matrix = &self.matrix;
@@ -269,4 +326,20 @@ impl Automaton {
 
         Ok(Automaton::from_integer_matrix(input))
     }
+
+    /// Writes the board in the format read by `from_file`, one row per line.
+    pub fn write_json<W: Write>(&self, mut writer: W) -> io::Result<()> {
+        let rows: Vec<String> = self
+            .to_integer_matrix()
+            .iter()
+            .map(|row| {
+                let cells: Vec<String> = row.iter().map(|cell| cell.to_string()).collect();
+
+                format!("  [{}]", cells.join(", "))
+            })
+            .collect();
+
+        writeln!(writer, "[\n{}\n]", rows.join(",\n"))?;
+        writer.flush()
+    }
 }
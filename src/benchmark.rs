@@ -3,13 +3,30 @@
 use std::time::Instant;
 
 use stone_challenge::{
-    automaton::Automaton, path_finder::shared::get_possible_moves,
+    automaton::Automaton, generator::generate, generator::GenerateOptions, path_finder::find_path,
+    path_finder::shared::get_possible_moves, path_finder::Solver,
     sparse_automaton::get_possible_moves as get_sparse_possible_moves,
     sparse_automaton::SparseAutomaton,
 };
 
+const ASTAR_BENCHMARK_INPUTS: [&str; 2] = ["first_challenge", "second_challenge"];
+const ASTAR_BENCHMARK_RUNS: u32 = 5;
+
+const PARALLEL_BENCHMARK_THREADS: [usize; 4] = [1, 2, 4, 8];
+const PARALLEL_BENCHMARK_RANDOM_SIZE: usize = 200;
+const PARALLEL_BENCHMARK_RANDOM_DENSITY: f64 = 0.3;
+const PARALLEL_BENCHMARK_SEED: u64 = 42;
+
 fn main() {
-    sparse();
+    let benchmark = std::env::args().nth(1).unwrap_or(String::from("sparse"));
+
+    match benchmark.as_str() {
+        "original" => original(),
+        "sparse" => sparse(),
+        "astar" => astar(),
+        "parallel" => parallel(),
+        _ => panic!("Unknown benchmark: {}", benchmark),
+    }
 }
 
 fn original() {
@@ -80,3 +97,89 @@ fn sparse() {
 
     println!("The elapsed time was: {:?}", end.duration_since(start));
 }
+
+fn astar() {
+    let mut report = vec![];
+
+    for input in ASTAR_BENCHMARK_INPUTS {
+        let automaton = Automaton::from_file(&format!("inputs/{}.json", input)).unwrap();
+
+        let mut total = std::time::Duration::ZERO;
+        let mut path_length = 0;
+
+        for _ in 0..ASTAR_BENCHMARK_RUNS {
+            let start = Instant::now();
+            let path = find_path(automaton.clone(), Solver::AStar).expect("Unable to find path");
+            total += start.elapsed();
+
+            path_length = path.len();
+        }
+
+        report.push((input, path_length, total / ASTAR_BENCHMARK_RUNS));
+    }
+
+    for (input, path_length, average) in report {
+        println!(
+            "{}: path length {}, average time over {} runs: {:?}",
+            input, path_length, ASTAR_BENCHMARK_RUNS, average
+        );
+    }
+}
+
+fn parallel() {
+    let automata = vec![
+        (
+            String::from("second_challenge"),
+            Automaton::from_file("inputs/second_challenge.json").unwrap(),
+        ),
+        (
+            format!("random {0}x{0}", PARALLEL_BENCHMARK_RANDOM_SIZE),
+            random_automaton(PARALLEL_BENCHMARK_RANDOM_SIZE),
+        ),
+    ];
+
+    let mut report = vec![];
+
+    for (name, automaton) in automata {
+        let mut single_thread_time = None;
+
+        for threads in PARALLEL_BENCHMARK_THREADS {
+            let start = Instant::now();
+            let path = find_path(automaton.clone(), Solver::Parallel(threads));
+            let elapsed = start.elapsed();
+
+            let single_thread_time = *single_thread_time.get_or_insert(elapsed);
+            let speedup = single_thread_time.as_secs_f64() / elapsed.as_secs_f64();
+
+            report.push((
+                name.clone(),
+                threads,
+                path.map(|path| path.len()),
+                elapsed,
+                speedup,
+            ));
+        }
+    }
+
+    println!(
+        "Available parallelism: {}",
+        std::thread::available_parallelism().map_or(1, |threads| threads.get())
+    );
+
+    for (name, threads, path_length, elapsed, speedup) in report {
+        println!(
+            "{}: {} threads, path length {:?}, time {:?}, speedup {:.2}x",
+            name, threads, path_length, elapsed, speedup
+        );
+    }
+}
+
+fn random_automaton(size: usize) -> Automaton {
+    let options = GenerateOptions {
+        density: PARALLEL_BENCHMARK_RANDOM_DENSITY,
+        seed: PARALLEL_BENCHMARK_SEED,
+        ..GenerateOptions::new(size, size)
+    };
+
+    generate(&options).unwrap().automaton
+}
@@ -2,3 +2,7 @@ pub const AUTOMATON_SNAPSHOTS_DIRECTORY: &str = "tmp";
 pub const GENERATION_STEP: u32 = 25;
 pub const AUTOMATON_FILE_PATH: &str = "inputs/final_pt_1.json";
 pub const REPORTING_ITERATION_STEP: u64 = 1000;
+pub const GENERATION_REPORTING_STEP: u32 = 100;
+pub const CHECKPOINT_ITERATION_STEP: u64 = 100000;
+pub const ORIGINAL_CALL_LIMIT: u64 = 10000;
+pub const ORIGINAL_GENERATION_LIMIT: u32 = 1000;
@@ -0,0 +1,186 @@
+use std::error::Error;
+use std::str::FromStr;
+
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+use crate::automaton::{Automaton, Position};
+use crate::path_finder::solve::{solve, SolveOptions, SolveResult};
+
+/// Boards tried before giving up on finding a solvable one, unless told otherwise.
+pub const DEFAULT_ATTEMPTS: u32 = 100;
+
+/// Chance of each cell being alive, unless told otherwise. Close to the one of
+/// the challenge inputs.
+pub const DEFAULT_DENSITY: f64 = 0.25;
+
+/// Where the start and the finish go.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Placement {
+    /// Start in the top left corner and finish in the bottom right one, as in
+    /// every input so far.
+    #[default]
+    Corners,
+    /// Two different cells drawn with the rest of the board.
+    Random,
+    Fixed {
+        start: Position,
+        finish: Position,
+    },
+}
+
+impl FromStr for Placement {
+    type Err = String;
+
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        match name {
+            "corners" => Ok(Placement::Corners),
+            "random" => Ok(Placement::Random),
+            _ => Err(format!("Unknown placement: {}", name)),
+        }
+    }
+}
+
+/// Keeps generating boards until the solver finds a path through one of them.
+#[derive(Clone, Debug)]
+pub struct Solvability {
+    /// A board on which the search runs out of its limits counts as unsolvable.
+    pub solve: SolveOptions,
+    pub attempts: u32,
+}
+
+impl Default for Solvability {
+    fn default() -> Self {
+        Solvability {
+            solve: SolveOptions::default(),
+            attempts: DEFAULT_ATTEMPTS,
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct GenerateOptions {
+    pub height: usize,
+    pub width: usize,
+    /// Chance of each cell being alive.
+    pub density: f64,
+    /// The same options and seed always give the same board.
+    pub seed: u64,
+    pub placement: Placement,
+    pub solvable: Option<Solvability>,
+}
+
+impl GenerateOptions {
+    pub fn new(height: usize, width: usize) -> Self {
+        GenerateOptions {
+            height,
+            width,
+            density: DEFAULT_DENSITY,
+            seed: 0,
+            placement: Placement::default(),
+            solvable: None,
+        }
+    }
+}
+
+pub struct Generated {
+    pub automaton: Automaton,
+    /// Boards generated, the returned one included.
+    pub attempts: u32,
+    /// Result of the solver on the returned board, if it had to be solvable.
+    pub solution: Option<SolveResult>,
+}
+
+/// Generates a random board at generation 1.
+///
+/// Every attempt draws from the same seeded generator, so retrying for a
+/// solvable board is as reproducible as a single draw.
+pub fn generate(options: &GenerateOptions) -> Result<Generated, Box<dyn Error>> {
+    let GenerateOptions {
+        height,
+        width,
+        density,
+        ..
+    } = *options;
+
+    if height * width < 2 {
+        return Err("The board needs at least two cells for the start and finish".into());
+    }
+
+    if !(0.0..=1.0).contains(&density) {
+        return Err(format!("Density must be between 0 and 1, not {}", density).into());
+    }
+
+    if let Placement::Fixed { start, finish } = options.placement {
+        for (name, (i, j)) in [("Start", start), ("Finish", finish)] {
+            if i >= height || j >= width {
+                return Err(format!("{} ({}, {}) is outside the board", name, i, j).into());
+            }
+        }
+
+        if start == finish {
+            return Err("The start and finish must be different cells".into());
+        }
+    }
+
+    let mut rng = StdRng::seed_from_u64(options.seed);
+
+    let Some(solvability) = &options.solvable else {
+        return Ok(Generated {
+            automaton: draw(options, &mut rng),
+            attempts: 1,
+            solution: None,
+        });
+    };
+
+    for attempt in 1..=solvability.attempts {
+        let automaton = draw(options, &mut rng);
+        let result = solve(&automaton, &solvability.solve)?;
+
+        if result.path().is_some() {
+            return Ok(Generated {
+                automaton,
+                attempts: attempt,
+                solution: Some(result),
+            });
+        }
+    }
+
+    Err(format!("No solvable board in {} attempts", solvability.attempts).into())
+}
+
+fn draw(options: &GenerateOptions, rng: &mut StdRng) -> Automaton {
+    let (height, width) = (options.height, options.width);
+
+    let mut matrix: Vec<Vec<i32>> = (0..height)
+        .map(|_| {
+            (0..width)
+                .map(|_| rng.gen_bool(options.density) as i32)
+                .collect()
+        })
+        .collect();
+
+    let (start, finish) = match options.placement {
+        Placement::Corners => ((0, 0), (height - 1, width - 1)),
+        Placement::Random => {
+            let start = rng.gen_range(0..height * width);
+            // Skipping the start keeps the finish uniform over the other cells
+            let mut finish = rng.gen_range(0..height * width - 1);
+
+            if finish >= start {
+                finish += 1;
+            }
+
+            (
+                (start / width, start % width),
+                (finish / width, finish % width),
+            )
+        }
+        Placement::Fixed { start, finish } => (start, finish),
+    };
+
+    matrix[start.0][start.1] = 2;
+    matrix[finish.0][finish.1] = 3;
+
+    Automaton::from_integer_matrix(matrix)
+}
@@ -1,4 +1,10 @@
 pub mod automaton;
 pub mod config;
+pub mod generator;
 pub mod path_finder;
+pub mod render;
+pub mod repair;
 pub mod sparse_automaton;
+pub mod stats;
+
+pub use path_finder::solve::{solve, SolveOptions, SolveResult};
@@ -1,56 +1,1083 @@
-mod automaton;
-mod benchmark;
-mod config;
-mod path_finder;
-mod sparse_automaton;
-
-use automaton::Automaton;
-use path_finder::find_path;
+use stone_challenge::automaton::{Automaton, CellState, Position};
+use stone_challenge::config::CHECKPOINT_ITERATION_STEP;
+use stone_challenge::generator::{
+    generate, GenerateOptions, Placement, Solvability, DEFAULT_ATTEMPTS, DEFAULT_DENSITY,
+};
+use stone_challenge::path_finder::observer::{
+    JsonLinesObserver, LogObserver, SearchObserver, SearchStats,
+};
+use stone_challenge::path_finder::shortening::{shorten_path_with_progress, DEFAULT_WINDOW};
+use stone_challenge::path_finder::{
+    find_path_with_checkpoint, find_path_with_observer, verify_result, Checkpointing, Interrupt,
+    Limit, PartialPath, SearchLimits, SearchOutcome, Solver, Start,
+};
+use stone_challenge::render::animation::write_gif;
+use stone_challenge::render::image::{draw, write_contact_sheet, write_png, View};
+use stone_challenge::render::svg::write_svg;
+use stone_challenge::render::terminal::replay;
+use stone_challenge::render::{parse_colour, Frames, RenderOptions};
+use stone_challenge::repair::{repair, DEFAULT_MAX_FLIPS};
+use stone_challenge::stats::default_horizon;
+use stone_challenge::stats::difficulty::rate;
+use stone_challenge::stats::generations::{write_report, Generations, ReportFormat};
+use stone_challenge::stats::heatmap::Heatmap;
+use stone_challenge::SolveOptions;
 
 use std::env;
-use std::fs::{read_to_string, File};
+use std::error::Error;
+use std::fs::{self, create_dir_all, read_to_string, File};
 use std::io::prelude::*;
+use std::io::{self, BufWriter};
+use std::path::Path;
+use std::process;
+use std::time::Duration;
+
+/// Exit code used when the search is stopped with Ctrl-C, as shells do for SIGINT.
+const INTERRUPTED_EXIT_CODE: i32 = 130;
+
+/// Exit code used when the search runs out of one of its limits.
+const LIMIT_EXCEEDED_EXIT_CODE: i32 = 2;
+
+/// Exit code used when the checkpoint to resume from cannot be loaded.
+const CHECKPOINT_ERROR_EXIT_CODE: i32 = 1;
+
+/// Options that do not take a value.
+const FLAGS: [&str; 3] = ["--force", "--resume", "--solvable"];
+
+/// Steps between the thumbnails of a contact sheet, unless `--every` is given.
+const DEFAULT_SHEET_EVERY: usize = 10;
+
+const DEFAULT_SHEET_COLUMNS: usize = 8;
+
+/// Commands given before the input name. Without one, the input is solved.
+const SUBCOMMANDS: [&str; 6] = [
+    "generate", "optimize", "render", "repair", "replay", "stats",
+];
+
+/// Reports of the `stats` command, given between `stats` and the input name.
+/// Without one, the stats of each generation are reported.
+const STATS_REPORTS: [&str; 2] = ["difficulty", "heatmap"];
 
-#[allow(unused_variables)]
 fn main() {
+    match get_subcommand().as_deref() {
+        Some("generate") => generate_board(),
+        Some("optimize") => optimize(),
+        Some("render") => render(),
+        Some("repair") => repair_board(),
+        Some("replay") => replay_in_terminal(),
+        Some("stats") => match get_stats_report().as_deref() {
+            Some("difficulty") => difficulty(),
+            Some("heatmap") => heatmap(),
+            _ => generation_report(),
+        },
+        _ => solve(),
+    }
+}
+
+#[allow(unused_variables)]
+fn solve() {
     let (input_file_path, output_file_path) = get_input_and_output_file_path();
+    let solver = get_solver();
+    let limits = get_search_limits();
 
-    let automaton = match Automaton::from_file(&input_file_path) {
-        Ok(automaton) => automaton,
-        Err(error) => panic!(
-            "Error loading automaton:\n {}\n Path: {}",
-            error, input_file_path
+    let automaton = load_automaton(&input_file_path);
+
+    let (automaton, output_file_path) = match get_start(&automaton) {
+        Some(start) => (
+            match start.apply(&automaton) {
+                Ok(automaton) => automaton,
+                Err(error) => panic!("Invalid start: {}", error),
+            },
+            get_start_output_file_path(&output_file_path, start),
         ),
+        None => (automaton, output_file_path),
     };
 
+    let checkpointing = get_checkpointing(&output_file_path);
+
     let best_result = get_current_best_result(&output_file_path);
     println!("Current best result: {}", best_result);
 
-    let path = find_path(automaton.clone()).expect("Unable to find path");
+    let mut observer = ResultRecorder {
+        observer: get_observer(),
+        output_file_path: output_file_path.clone(),
+        best_result,
+    };
+
+    let interrupt = Interrupt::new();
+    install_interrupt_handler(&interrupt);
+
+    let outcome = match checkpointing {
+        Some(checkpointing) => {
+            if solver != Solver::AStar {
+                panic!("Checkpoints are only supported by the astar solver");
+            }
+
+            let outcome = find_path_with_checkpoint(
+                automaton.clone(),
+                &checkpointing,
+                limits,
+                &interrupt,
+                &mut observer,
+            );
+
+            match outcome {
+                Ok(outcome) => outcome,
+                Err(error) => {
+                    println!("{}", error);
+                    process::exit(CHECKPOINT_ERROR_EXIT_CODE);
+                }
+            }
+        }
+        None => {
+            find_path_with_observer(automaton.clone(), solver, limits, &interrupt, &mut observer)
+        }
+    };
+
+    let path = match outcome {
+        SearchOutcome::Found(path) => path,
+        SearchOutcome::NoPath => {
+            panic!("Unable to find path, the repair command can make the board solvable")
+        }
+        SearchOutcome::LimitExceeded { which, partial } => {
+            println!("Search stopped: {} limit exceeded", limit_name(which));
+            report_partial_path(partial);
+            process::exit(LIMIT_EXCEEDED_EXIT_CODE);
+        }
+        SearchOutcome::Interrupted(partial) => {
+            println!("Search interrupted");
+            report_partial_path(partial);
+            process::exit(INTERRUPTED_EXIT_CODE);
+        }
+    };
     println!("Found path with length: {}", path.len());
+}
+
+/// Writes every improvement to the output file as soon as it is found, so an
+/// interrupted anytime run still leaves its best path on disk.
+struct ResultRecorder {
+    observer: Box<dyn SearchObserver>,
+    output_file_path: String,
+    best_result: usize,
+}
+
+impl SearchObserver for ResultRecorder {
+    fn on_expand(&mut self, stats: &SearchStats) {
+        self.observer.on_expand(stats);
+    }
+
+    fn on_generation_computed(&mut self, generation: u32) {
+        self.observer.on_generation_computed(generation);
+    }
+
+    fn on_improvement(&mut self, path: &[Position]) {
+        self.observer.on_improvement(path);
+
+        if path.len() < self.best_result {
+            if let Err(error) = write_result(&self.output_file_path, path) {
+                println!("Unable to write {}: {}", self.output_file_path, error);
+                return;
+            }
+
+            println!("New best result: {}", path.len());
+            self.best_result = path.len();
+        }
+    }
+
+    fn on_heuristic_weight(&mut self, weight: f32) {
+        self.observer.on_heuristic_weight(weight);
+    }
+
+    fn on_resume(&mut self, iteration: u64) {
+        self.observer.on_resume(iteration);
+    }
+
+    fn on_checkpoint_error(&mut self, error: &dyn Error) {
+        self.observer.on_checkpoint_error(error);
+    }
+
+    fn on_finish(&mut self, outcome: &SearchOutcome, stats: &SearchStats) {
+        self.observer.on_finish(outcome, stats);
+    }
+}
+
+/// `--events <path>` writes the progress of the search to `path` as JSON
+/// lines, instead of printing it.
+fn get_observer() -> Box<dyn SearchObserver> {
+    match get_option("--events") {
+        Some(events_file_path) => {
+            let file = match File::create(&events_file_path) {
+                Ok(file) => file,
+                Err(error) => panic!("Error creating {}: {}", events_file_path, error),
+            };
+
+            Box::new(JsonLinesObserver::new(BufWriter::new(file)))
+        }
+        None => Box::new(LogObserver::default()),
+    }
+}
+
+/// `optimize <name>` shortens the path in `outputs/<name>.result.json` with
+/// detours of up to `--window` generations, and writes it back if shorter.
+fn optimize() {
+    let (input_file_path, output_file_path) = get_input_and_output_file_path();
+
+    let automaton = load_automaton(&input_file_path);
+    let path = read_path(&output_file_path);
+
+    if path.is_empty() {
+        panic!("No path to optimize in {}", output_file_path);
+    }
+
+    if !verify_result(&automaton, &path) {
+        panic!("Path in {} is not valid", output_file_path);
+    }
+
+    let window = match get_option("--window") {
+        Some(window) => window.parse().expect("Invalid --window"),
+        None => DEFAULT_WINDOW,
+    };
+
+    let shortened = shorten_path_with_progress(&automaton, &path, window, &mut |detour| {
+        println!(
+            "Replaced steps {} to {} with {} steps",
+            detour.from, detour.to, detour.steps
+        )
+    });
+    println!("Path length: {} -> {}", path.len(), shortened.len());
+
+    if shortened.len() < path.len() {
+        let mut file = File::create(&output_file_path).unwrap();
+        writeln!(file, "{}", path_to_json(&shortened)).unwrap();
+        println!("Shortened path written to {}", output_file_path);
+    }
+}
+
+/// `render <name>` draws the path in `outputs/<name>.result.json` (or
+/// `--result`) over the evolving board, in the `--format`:
+/// - `gif` (default): an animation, written to `outputs/<name>.gif`
+/// - `png`: one image every `--every` steps, in `outputs/<name>.frames/`
+/// - `sheet`: thumbnails every `--every` steps, in `outputs/<name>.sheet.png`
+/// - `svg`: the board at `--generation` with the whole path, in
+///   `outputs/<name>.svg`
+///
+/// `--output` changes where they are written.
+fn render() {
+    let (input_file_path, output_file_path) = get_input_and_output_file_path();
+    let result_file_path = get_option("--result").unwrap_or(output_file_path);
+
+    let automaton = load_automaton(&input_file_path);
+    let path = read_path(&result_file_path);
+
+    if !verify_result(&automaton, &path) {
+        println!(
+            "Path in {} is not valid, rendering it anyway",
+            result_file_path
+        );
+    }
+
+    let format = get_option("--format").unwrap_or(String::from("gif"));
+
+    match format.as_str() {
+        "gif" => render_gif(&automaton, &path, &result_file_path),
+        "png" => render_png_frames(&automaton, &path, &result_file_path),
+        "sheet" => render_contact_sheet(&automaton, &path, &result_file_path),
+        "svg" => render_svg(&automaton, &path, &result_file_path),
+        _ => panic!("Unknown render format: {}", format),
+    }
+}
+
+/// `replay <name>` steps through the path in `outputs/<name>.result.json` (or
+/// `--result`) in the terminal.
+fn replay_in_terminal() {
+    let (input_file_path, output_file_path) = get_input_and_output_file_path();
+    let result_file_path = get_option("--result").unwrap_or(output_file_path);
+
+    let automaton = load_automaton(&input_file_path);
+    let path = read_path(&result_file_path);
 
-    if path.len() < best_result {
-        let mut file = File::create(output_file_path).unwrap();
-        writeln!(file, "{}", path_to_json(&path)).unwrap();
-        println!("New best result: {}", path.len());
+    if let Err(error) = replay(&automaton, &path) {
+        panic!("Error replaying {}: {}", result_file_path, error);
     }
 }
 
-fn path_to_json(result: &Vec<(usize, usize)>) -> String {
+fn render_gif(automaton: &Automaton, path: &[Position], result_file_path: &str) {
+    let gif_file_path =
+        get_option("--output").unwrap_or(result_file_path.replace(".result.json", ".gif"));
+
+    let options = get_render_options(RenderOptions::default().cell_size);
+
+    match write_gif(automaton, path, &options, create_file(&gif_file_path)) {
+        Ok(()) => println!("Rendered {} frames to {}", path.len(), gif_file_path),
+        Err(error) => panic!("Error rendering {}: {}", gif_file_path, error),
+    }
+}
+
+/// One cell per pixel unless `--cell-size` or `--downsample` say otherwise.
+fn render_png_frames(automaton: &Automaton, path: &[Position], result_file_path: &str) {
+    let directory =
+        get_option("--output").unwrap_or(result_file_path.replace(".result.json", ".frames"));
+
+    if let Err(error) = create_dir_all(&directory) {
+        panic!("Error creating {}: {}", directory, error);
+    }
+
+    let every = get_every(1);
+    let options = get_render_options(1);
+    let view = get_view();
+    let last_step = path.len().saturating_sub(1);
+
+    for frame in Frames::new(automaton, path) {
+        if !frame.step.is_multiple_of(every) && frame.step != last_step {
+            continue;
+        }
+
+        let png_file_path = format!("{}/generation_{}.png", directory, frame.generation);
+
+        if let Err(error) = write_png(&draw(&frame, &view, &options), create_file(&png_file_path)) {
+            panic!("Error rendering {}: {}", png_file_path, error);
+        }
+
+        println!("Rendered {}", png_file_path);
+    }
+}
+
+/// `--columns` thumbnails to a row, 8 by default.
+fn render_contact_sheet(automaton: &Automaton, path: &[Position], result_file_path: &str) {
+    let sheet_file_path =
+        get_option("--output").unwrap_or(result_file_path.replace(".result.json", ".sheet.png"));
+
+    let columns = match get_option("--columns") {
+        Some(columns) => columns.parse().expect("Invalid --columns"),
+        None => DEFAULT_SHEET_COLUMNS,
+    };
+
+    let result = write_contact_sheet(
+        automaton,
+        path,
+        get_every(DEFAULT_SHEET_EVERY),
+        columns,
+        &get_view(),
+        &get_render_options(1),
+        create_file(&sheet_file_path),
+    );
+
+    match result {
+        Ok(()) => println!("Rendered contact sheet to {}", sheet_file_path),
+        Err(error) => panic!("Error rendering {}: {}", sheet_file_path, error),
+    }
+}
+
+fn render_svg(automaton: &Automaton, path: &[Position], result_file_path: &str) {
+    let svg_file_path =
+        get_option("--output").unwrap_or(result_file_path.replace(".result.json", ".svg"));
+
+    let generation = match get_option("--generation") {
+        Some(generation) => generation.parse().expect("Invalid --generation"),
+        None => automaton.generation,
+    };
+
+    let options = get_render_options(RenderOptions::default().cell_size);
+
+    match write_svg(
+        automaton,
+        path,
+        generation,
+        &options,
+        create_file(&svg_file_path),
+    ) {
+        Ok(()) => println!("Rendered generation {} to {}", generation, svg_file_path),
+        Err(error) => panic!("Error rendering {}: {}", svg_file_path, error),
+    }
+}
+
+/// `generate <name> --height <rows> --width <columns>` writes a random board
+/// to `inputs/<name>.json`, or `--output`, refusing to overwrite an existing
+/// file without `--force`.
+///
+/// `--density` is the chance of each cell being alive and `--seed` makes the
+/// board reproducible; a random one is printed otherwise. The start and
+/// finish go in the corners, at random cells with `--placement random`, or at
+/// `--start <row>,<column>` and `--finish <row>,<column>`.
+///
+/// With `--solvable`, boards are generated until the solver (with the same
+/// options as when solving) finds a path, up to `--attempts` boards. The path
+/// is saved next to the board as in `repair`, to `outputs/<name>.result.json`
+/// by default, also refusing to overwrite it without `--force`.
+fn generate_board() {
+    let name = get_positional_arguments()
+        .get(1)
+        .cloned()
+        .expect("Missing name of the board to generate");
+
+    let dimension = |option: &str| -> usize {
+        get_option(option)
+            .unwrap_or_else(|| panic!("Missing {}", option))
+            .parse()
+            .unwrap_or_else(|_| panic!("Invalid {}", option))
+    };
+
+    let placement = match (get_option("--start"), get_option("--finish")) {
+        (Some(start), Some(finish)) => Placement::Fixed {
+            start: parse_position(&start, "--start"),
+            finish: parse_position(&finish, "--finish"),
+        },
+        (None, None) => match get_option("--placement") {
+            Some(name) => name.parse().unwrap_or_else(|error| panic!("{}", error)),
+            None => Placement::default(),
+        },
+        _ => panic!("--start and --finish must be given together"),
+    };
+
+    let solvable = has_flag("--solvable").then(|| Solvability {
+        solve: SolveOptions {
+            solver: get_solver(),
+            limits: get_search_limits(),
+            ..SolveOptions::default()
+        },
+        attempts: match get_option("--attempts") {
+            Some(attempts) => attempts.parse().expect("Invalid --attempts"),
+            None => DEFAULT_ATTEMPTS,
+        },
+    });
+
+    let options = GenerateOptions {
+        height: dimension("--height"),
+        width: dimension("--width"),
+        density: match get_option("--density") {
+            Some(density) => density.parse().expect("Invalid --density"),
+            None => DEFAULT_DENSITY,
+        },
+        seed: match get_option("--seed") {
+            Some(seed) => seed.parse().expect("Invalid --seed"),
+            None => rand::random(),
+        },
+        placement,
+        solvable,
+    };
+
+    let board_file_path = get_option("--output").unwrap_or(format!("inputs/{}.json", name));
+    let result_file_path = result_file_path(&board_file_path);
+
+    let mut written_files = vec![&board_file_path];
+
+    if options.solvable.is_some() {
+        written_files.push(&result_file_path);
+    }
+
+    for file_path in written_files {
+        if Path::new(file_path).exists() && !has_flag("--force") {
+            panic!("{} already exists, use --force to overwrite it", file_path);
+        }
+    }
+
+    let generated = match generate(&options) {
+        Ok(generated) => generated,
+        Err(error) => panic!("Error generating board: {}", error),
+    };
+
+    if let Err(error) = generated
+        .automaton
+        .write_json(create_file(&board_file_path))
+    {
+        panic!("Error writing {}: {}", board_file_path, error);
+    }
+
+    println!(
+        "Generated {}x{} board with seed {} in {} attempt(s): {}",
+        options.height, options.width, options.seed, generated.attempts, board_file_path
+    );
+
+    if let Some(path) = generated.solution.as_ref().and_then(|result| result.path()) {
+        println!("Found path with length: {}", path.len());
+
+        let mut file = create_file(&result_file_path);
+
+        if let Err(error) = writeln!(file, "{}", path_to_json(path)) {
+            panic!("Error writing {}: {}", result_file_path, error);
+        }
+
+        println!("Path written to {}", result_file_path);
+    }
+}
+
+/// Where the path through a board goes: `.json` becomes `.result.json`, and
+/// `inputs/` becomes `outputs/`.
+fn result_file_path(board_file_path: &str) -> String {
+    let stem = board_file_path
+        .strip_suffix(".json")
+        .unwrap_or(board_file_path);
+
+    format!("{}.result.json", stem.replacen("inputs/", "outputs/", 1))
+}
+
+/// `repair <name>` looks for the fewest cells to flip in the first generation
+/// for the board to have a path, up to `--max-flips`. The repaired board is
+/// written to `inputs/<name>.repaired.json`, or `--output`, and its path to
+/// the matching result file, so it can be solved, rendered or replayed.
+/// Neither file is overwritten without `--force`.
+fn repair_board() {
+    let (input_file_path, _) = get_input_and_output_file_path();
+
+    let board_file_path =
+        get_option("--output").unwrap_or(input_file_path.replace(".json", ".repaired.json"));
+    let result_file_path = result_file_path(&board_file_path);
+
+    for file_path in [&board_file_path, &result_file_path] {
+        if Path::new(file_path).exists() && !has_flag("--force") {
+            panic!("{} already exists, use --force to overwrite it", file_path);
+        }
+    }
+
+    let automaton = load_automaton(&input_file_path);
+
+    let max_flips = match get_option("--max-flips") {
+        Some(max_flips) => max_flips.parse().expect("Invalid --max-flips"),
+        None => DEFAULT_MAX_FLIPS,
+    };
+
+    let repair = match repair(&automaton, max_flips) {
+        Ok(repair) => repair,
+        Err(error) => panic!("Unable to repair {}: {}", input_file_path, error),
+    };
+
+    println!("Boards evaluated: {}", repair.evaluated_boards);
+
+    if repair.flips.is_empty() {
+        println!("The board is already solvable");
+        return;
+    }
+
+    println!(
+        "Flipped {} cell(s){}:",
+        repair.flips.len(),
+        if repair.proven_minimum {
+            ""
+        } else {
+            ", possibly not the fewest"
+        }
+    );
+
+    for (i, j) in &repair.flips {
+        let state = if automaton.matrix[*i][*j] == CellState::Alive {
+            "alive to dead"
+        } else {
+            "dead to alive"
+        };
+
+        println!("  ({}, {}) {}", i, j, state);
+    }
+
+    println!("Found path with length: {}", repair.path.len());
+
+    if let Err(error) = repair.automaton.write_json(create_file(&board_file_path)) {
+        panic!("Error writing {}: {}", board_file_path, error);
+    }
+
+    if let Err(error) = writeln!(
+        create_file(&result_file_path),
+        "{}",
+        path_to_json(&repair.path)
+    ) {
+        panic!("Error writing {}: {}", result_file_path, error);
+    }
+
+    println!("Repaired board written to {}", board_file_path);
+    println!("Path written to {}", result_file_path);
+}
+
+/// `stats <name>` reports the population, births, deaths, bounding box and
+/// whether the start and finish are walled in, for each of `--horizon`
+/// generations (as many as the solvers precompute by default). The
+/// `--format` is `table` (default), `csv` or `json`, written as each
+/// generation is computed to the standard output, or `--output`.
+fn generation_report() {
+    let (input_file_path, _) = get_input_and_output_file_path();
+
+    let automaton = load_automaton(&input_file_path);
+
+    let horizon = get_horizon(&automaton);
+
+    let format: ReportFormat = match get_option("--format") {
+        Some(name) => name.parse().unwrap_or_else(|error| panic!("{}", error)),
+        None => ReportFormat::default(),
+    };
+
+    let generations = Generations::new(&automaton, horizon);
+
+    let result = match get_option("--output") {
+        Some(report_file_path) => write_report(generations, format, create_file(&report_file_path)),
+        None => write_report(generations, format, io::stdout().lock()),
+    };
+
+    if let Err(error) = result {
+        panic!("Error writing report: {}", error);
+    }
+}
+
+/// `stats difficulty <name>` rates how hard the board is to cross within
+/// `--horizon` generations, as a summary or, with `--format json`, as JSON
+/// including the reachable states of every generation.
+fn difficulty() {
+    let (input_file_path, _) = get_input_and_output_file_path();
+
+    let automaton = load_automaton(&input_file_path);
+    let horizon = get_horizon(&automaton);
+    let difficulty = rate(&automaton, horizon);
+
+    match get_option("--format").as_deref() {
+        None | Some("text") => (),
+        Some("json") => {
+            println!("{}", serde_json::to_string_pretty(&difficulty).unwrap());
+            return;
+        }
+        Some(format) => panic!("Unknown difficulty format: {}", format),
+    }
+
+    println!("Manhattan distance: {}", difficulty.manhattan_distance);
+
+    match (difficulty.optimal_moves, difficulty.detour) {
+        (Some(moves), Some(detour)) => {
+            println!("Optimal moves: {} (detour {:.2})", moves, detour)
+        }
+        _ => println!("Optimal moves: no path within {} generations", horizon),
+    }
+
+    let reachable = &difficulty.reachable_states;
+    let widest = (0..reachable.len()).max_by_key(|index| reachable[*index]);
+
+    if let Some(index) = widest {
+        println!(
+            "Widest frontier: {} reachable states at generation {}",
+            reachable[index],
+            difficulty.first_generation + index as u32
+        );
+    }
+
+    let bottlenecks: Vec<String> = difficulty
+        .bottlenecks
+        .iter()
+        .map(|bottleneck| {
+            format!(
+                "generation {} ({} states)",
+                bottleneck.generation, bottleneck.reachable_states
+            )
+        })
+        .collect();
+
+    println!(
+        "Bottlenecks: {}",
+        if bottlenecks.is_empty() {
+            String::from("none")
+        } else {
+            bottlenecks.join(", ")
+        }
+    );
+
+    match difficulty.score {
+        Some(score) => println!("Difficulty score: {:.2}", score),
+        None => println!("Difficulty score: unsolvable"),
+    }
+}
+
+/// `stats heatmap <name>` counts how often each cell is alive over
+/// `--horizon` generations (as many as the solvers precompute by default), and
+/// writes it in the `--format`:
+/// - `png` (default): one pixel per cell, darker the more often it is alive
+/// - `csv`: the alive counts, one line per row
+/// - `json`: the alive counts, and the first and last free generation of
+///   each cell
+///
+/// to `outputs/<name>.heatmap.<format>`, or `--output`.
+fn heatmap() {
+    let (input_file_path, output_file_path) = get_input_and_output_file_path();
+
+    let automaton = load_automaton(&input_file_path);
+
+    let horizon = get_horizon(&automaton);
+
+    let format = get_option("--format").unwrap_or(String::from("png"));
+
+    if !["png", "csv", "json"].contains(&format.as_str()) {
+        panic!("Unknown heatmap format: {}", format);
+    }
+    let heatmap_file_path = get_option("--output")
+        .unwrap_or(output_file_path.replace(".result.json", &format!(".heatmap.{}", format)));
+
+    let heatmap = Heatmap::new(&automaton, horizon);
+    let mut file = create_file(&heatmap_file_path);
+
+    let result = match format.as_str() {
+        "png" => write_png(&heatmap.to_image(&automaton, &get_render_options(1)), file),
+        "csv" => heatmap.write_csv(file).map_err(|error| error.into()),
+        _ => writeln!(file, "{}", heatmap.to_json())
+            .and_then(|()| file.flush())
+            .map_err(|error| error.into()),
+    };
+
+    match result {
+        Ok(()) => println!(
+            "Heatmap of {} generations written to {}",
+            horizon, heatmap_file_path
+        ),
+        Err(error) => panic!("Error writing {}: {}", heatmap_file_path, error),
+    }
+}
+
+/// `--horizon <generations>`, defaulting to as many as the solvers precompute.
+fn get_horizon(automaton: &Automaton) -> u32 {
+    match get_option("--horizon") {
+        Some(horizon) => horizon.parse().expect("Invalid --horizon"),
+        None => default_horizon(automaton),
+    }
+}
+
+fn create_file(file_path: &str) -> BufWriter<File> {
+    match File::create(file_path) {
+        Ok(file) => BufWriter::new(file),
+        Err(error) => panic!("Error creating {}: {}", file_path, error),
+    }
+}
+
+fn get_every(default: usize) -> usize {
+    match get_option("--every") {
+        Some(every) => every.parse().expect("Invalid --every"),
+        None => default,
+    }
+}
+
+/// `--downsample <cells>` draws each square of cells as one, `--crop <radius>`
+/// only draws the cells around the particle.
+fn get_view() -> View {
+    View {
+        downsample: match get_option("--downsample") {
+            Some(downsample) => match downsample.parse() {
+                Ok(downsample) if downsample > 0 => downsample,
+                _ => panic!("Invalid --downsample"),
+            },
+            None => 1,
+        },
+        crop_radius: get_option("--crop").map(|radius| radius.parse().expect("Invalid --crop")),
+    }
+}
+
+/// `--cell-size <pixels>`, `--fps <frames>` and `--<tile>-colour <#rrggbb>`
+/// for the dead, alive, trail, particle, start and finish tiles.
+fn get_render_options(default_cell_size: usize) -> RenderOptions {
+    let mut options = RenderOptions {
+        cell_size: default_cell_size,
+        ..RenderOptions::default()
+    };
+
+    if let Some(cell_size) = get_option("--cell-size") {
+        options.cell_size = cell_size.parse().expect("Invalid --cell-size");
+    }
+
+    if let Some(frames_per_second) = get_option("--fps") {
+        options.frames_per_second = frames_per_second.parse().expect("Invalid --fps");
+    }
+
+    let colours = [
+        ("--dead-colour", &mut options.dead_colour),
+        ("--alive-colour", &mut options.alive_colour),
+        ("--trail-colour", &mut options.trail_colour),
+        ("--particle-colour", &mut options.particle_colour),
+        ("--start-colour", &mut options.start_colour),
+        ("--finish-colour", &mut options.finish_colour),
+    ];
+
+    for (name, colour) in colours {
+        if let Some(hex) = get_option(name) {
+            *colour = parse_colour(&hex).unwrap_or_else(|error| panic!("{}", error));
+        }
+    }
+
+    options
+}
+
+fn load_automaton(input_file_path: &str) -> Automaton {
+    match Automaton::from_file(input_file_path) {
+        Ok(automaton) => automaton,
+        Err(error) => panic!(
+            "Error loading automaton:\n {}\n Path: {}",
+            error, input_file_path
+        ),
+    }
+}
+
+/// The first Ctrl-C asks the search to stop and report how far it got,
+/// a second one exits right away.
+fn install_interrupt_handler(interrupt: &Interrupt) {
+    let interrupt = interrupt.clone();
+
+    let result = ctrlc::set_handler(move || {
+        if interrupt.is_triggered() {
+            process::exit(INTERRUPTED_EXIT_CODE);
+        }
+
+        println!("Interrupted, stopping the search...");
+        interrupt.trigger();
+    });
+
+    if let Err(error) = result {
+        println!("Unable to install Ctrl-C handler: {}", error);
+    }
+}
+
+/// Prints where the search stalled and, with `--partial-output <path>`,
+/// writes the path to the closest state in the same format as results.
+fn report_partial_path(partial: Option<PartialPath>) {
+    let partial = match partial {
+        Some(partial) => partial,
+        None => {
+            println!("No state was expanded");
+            return;
+        }
+    };
+
+    println!("Closest state generation: {}", partial.generation);
+    println!("Closest state position: {:?}", partial.position);
+    println!("Distance to finish: {}", partial.distance_to_finish);
+
+    if let Some(partial_output_file_path) = get_option("--partial-output") {
+        let mut file = File::create(&partial_output_file_path).unwrap();
+        writeln!(file, "{}", path_to_json(&partial.path)).unwrap();
+        println!("Partial path written to {}", partial_output_file_path);
+    }
+}
+
+/// Writes a path to a temporary file first and then moves it over
+/// `file_path`, so the previous result survives an exit in the middle of
+/// writing, such as a second Ctrl-C.
+fn write_result(file_path: &str, path: &[Position]) -> io::Result<()> {
+    let temporary_path = format!("{}.tmp", file_path);
+
+    let mut writer = BufWriter::new(File::create(&temporary_path)?);
+    writeln!(writer, "{}", path_to_json(path))?;
+    writer.into_inner()?.sync_all()?;
+
+    fs::rename(temporary_path, file_path)
+}
+
+fn path_to_json(result: &[(usize, usize)]) -> String {
     let arr: Vec<[usize; 2]> = result.iter().map(|(x, y)| [*x, *y]).collect();
 
     serde_json::to_string(&arr).unwrap()
 }
 
 fn get_input_and_output_file_path() -> (String, String) {
-    let args: Vec<String> = env::args().collect();
+    let skipped = get_subcommand().iter().count() + get_stats_report().iter().count();
 
-    let file = args.get(1).unwrap_or(&String::from("sample")).clone();
+    let file = get_positional_arguments()
+        .get(skipped)
+        .cloned()
+        .unwrap_or(String::from("sample"));
 
     let input_file_path = format!("inputs/{}.json", file);
     let output_file_path = format!("outputs/{}.result.json", file);
 
-    return (input_file_path, output_file_path);
+    (input_file_path, output_file_path)
+}
+
+fn get_solver() -> Solver {
+    let solver = match get_option("--solver") {
+        Some(name) => name.parse().unwrap_or_else(|error| panic!("{}", error)),
+        None => Solver::AStar,
+    };
+
+    match solver {
+        Solver::Beam(mut options) => {
+            if let Some(width) = get_option("--beam-width") {
+                options.width = width.parse().expect("Invalid --beam-width");
+            }
+
+            if let Some(region_size) = get_option("--beam-region") {
+                options.region_size = region_size.parse().expect("Invalid --beam-region");
+            }
+
+            Solver::Beam(options)
+        }
+        Solver::Original(mut options) => {
+            if let Some(seed) = get_option("--seed") {
+                options.seed = seed.parse().expect("Invalid --seed");
+            }
+
+            if let Some(restarts) = get_option("--restarts") {
+                options.restarts = restarts.parse().expect("Invalid --restarts");
+            }
+
+            Solver::Original(options)
+        }
+        Solver::Parallel(threads) => match get_option("--threads") {
+            Some(threads) => Solver::Parallel(threads.parse().expect("Invalid --threads")),
+            None => Solver::Parallel(threads),
+        },
+        solver => solver,
+    }
+}
+
+/// `--checkpoint <path>` saves the A* search every `CHECKPOINT_ITERATION_STEP`
+/// iterations (or `--checkpoint-interval`), `--resume` continues from it.
+/// Without `--checkpoint`, `--resume` looks next to the output file.
+fn get_checkpointing(output_file_path: &str) -> Option<Checkpointing> {
+    let path = get_option("--checkpoint");
+    let resume = has_flag("--resume");
+
+    if path.is_none() && !resume {
+        return None;
+    }
+
+    let interval = match get_option("--checkpoint-interval") {
+        Some(interval) => interval.parse().expect("Invalid --checkpoint-interval"),
+        None => CHECKPOINT_ITERATION_STEP,
+    };
+
+    Some(Checkpointing {
+        path: path.unwrap_or(output_file_path.replace(".result.json", ".checkpoint.json")),
+        interval,
+        resume,
+    })
+}
+
+/// `--start-generation <generation>` and `--start-position <row>,<column>`,
+/// each defaulting to the one of `automaton`.
+fn get_start(automaton: &Automaton) -> Option<Start> {
+    let generation = get_option("--start-generation");
+    let position = get_option("--start-position");
+
+    if generation.is_none() && position.is_none() {
+        return None;
+    }
+
+    let generation = match generation {
+        Some(generation) => generation.parse().expect("Invalid --start-generation"),
+        None => automaton.generation,
+    };
+
+    let position = match position {
+        Some(position) => parse_position(&position, "--start-position"),
+        None => automaton.starting_point,
+    };
+
+    Some(Start {
+        generation,
+        position,
+    })
+}
+
+/// `<row>,<column>`, as given to `option`.
+fn parse_position(value: &str, option: &str) -> Position {
+    let invalid = format!("Invalid {}", option);
+    let (i, j) = value.split_once(',').expect(&invalid);
+
+    (
+        i.trim().parse().expect(&invalid),
+        j.trim().parse().expect(&invalid),
+    )
+}
+
+/// Paths from another start are not results for the input itself, so they
+/// are kept apart, e.g. `outputs/sample.from_10_3_4.result.json`.
+fn get_start_output_file_path(output_file_path: &str, start: Start) -> String {
+    let (i, j) = start.position;
+
+    output_file_path.replace(
+        ".result.json",
+        &format!(".from_{}_{}_{}.result.json", start.generation, i, j),
+    )
+}
+
+/// `--time-limit <seconds>`, `--max-expanded <states>`,
+/// `--max-generation <generation>` and `--memory-limit <megabytes>`.
+fn get_search_limits() -> SearchLimits {
+    SearchLimits {
+        wall_time: get_option("--time-limit")
+            .map(|seconds| Duration::from_secs_f64(seconds.parse().expect("Invalid --time-limit"))),
+        expanded_states: get_option("--max-expanded")
+            .map(|states| states.parse().expect("Invalid --max-expanded")),
+        max_generation: get_option("--max-generation")
+            .map(|generation| generation.parse().expect("Invalid --max-generation")),
+        memory_bytes: get_option("--memory-limit").map(|megabytes| {
+            megabytes.parse::<usize>().expect("Invalid --memory-limit") * 1024 * 1024
+        }),
+    }
+}
+
+fn limit_name(limit: Limit) -> &'static str {
+    match limit {
+        Limit::WallTime => "time",
+        Limit::ExpandedStates => "expanded states",
+        Limit::MaxGeneration => "generation",
+        Limit::Memory => "memory",
+    }
+}
+
+fn get_subcommand() -> Option<String> {
+    get_positional_arguments()
+        .first()
+        .filter(|argument| SUBCOMMANDS.contains(&argument.as_str()))
+        .cloned()
+}
+
+fn get_stats_report() -> Option<String> {
+    get_subcommand().filter(|subcommand| subcommand == "stats")?;
+
+    get_positional_arguments()
+        .get(1)
+        .filter(|argument| STATS_REPORTS.contains(&argument.as_str()))
+        .cloned()
+}
+
+fn has_flag(name: &str) -> bool {
+    env::args().any(|arg| arg == name)
+}
+
+/// Value following `name` in the command line arguments, e.g. `--solver bfs`.
+fn get_option(name: &str) -> Option<String> {
+    let args: Vec<String> = env::args().collect();
+
+    let index = args.iter().position(|arg| arg == name)?;
+
+    match args.get(index + 1) {
+        Some(value) => Some(value.clone()),
+        None => panic!("Missing value for {}", name),
+    }
+}
+
+/// Command line arguments that are neither options nor option values.
+fn get_positional_arguments() -> Vec<String> {
+    let args: Vec<String> = env::args().skip(1).collect();
+
+    let mut positional = vec![];
+    let mut index = 0;
+
+    while index < args.len() {
+        if FLAGS.contains(&args[index].as_str()) {
+            index += 1;
+            continue;
+        }
+
+        if args[index].starts_with("--") {
+            index += 2;
+            continue;
+        }
+
+        positional.push(args[index].clone());
+        index += 1;
+    }
+
+    positional
+}
+
+fn read_path(output_file_path: &str) -> Vec<Position> {
+    let json = match read_to_string(output_file_path) {
+        Ok(content) => content,
+        Err(error) => panic!("Error reading {}: {}", output_file_path, error),
+    };
+
+    let path: Vec<[usize; 2]> = serde_json::from_str(&json).unwrap();
+
+    path.iter().map(|[i, j]| (*i, *j)).collect()
 }
 
 fn get_current_best_result(output_file_path: &String) -> usize {
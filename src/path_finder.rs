@@ -1,51 +1,215 @@
 use crate::automaton::{Automaton, CellState, Position};
-use std::time::Instant;
+pub use astar::checkpoint::Checkpointing;
+use beam::BeamOptions;
+use context::SearchContext;
+use observer::{LogObserver, SearchObserver};
+use original::DfsOptions;
+pub use outcome::{Interrupt, Limit, PartialPath, SearchLimits, SearchOutcome};
+use solve::SolveResult;
+use std::error::Error;
+use std::str::FromStr;
+use verification::verify_path;
+mod anytime;
 mod astar;
-mod original;
+pub mod beam;
+mod bfs;
+mod bidirectional;
+pub mod context;
+mod dead_ends;
+pub mod observer;
+pub mod original;
+pub mod outcome;
+pub mod reachability;
 pub mod shared;
+pub mod shortening;
+pub mod solve;
+pub mod verification;
 
-use astar::path_finder;
+pub const DEFAULT_THREADS: usize = 4;
 
-pub fn find_path(automaton: Automaton) -> Option<Vec<Position>> {
-    if automaton.generation != 1 {
-        panic!("Automaton must be in generation 1");
+/// Where and when the particle starts, instead of the starting point of the
+/// automaton at its current generation.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct Start {
+    pub generation: u32,
+    pub position: Position,
+}
+
+impl Start {
+    /// `automaton` advanced to the starting generation, with its starting
+    /// point moved to the starting position.
+    ///
+    /// Fails if the position is outside the board or on a cell alive at the
+    /// starting generation, or if that generation is before the current one.
+    pub fn apply(&self, automaton: &Automaton) -> Result<Automaton, Box<dyn Error>> {
+        let (i, j) = self.position;
+        let height = automaton.matrix.len();
+        let width = automaton.matrix[0].len();
+
+        if i >= height || j >= width {
+            return Err(format!(
+                "Start ({}, {}) is outside the {}x{} board",
+                i, j, height, width
+            )
+            .into());
+        }
+
+        if self.generation < automaton.generation {
+            return Err(format!(
+                "Start generation {} is before the current generation {}",
+                self.generation, automaton.generation
+            )
+            .into());
+        }
+
+        let mut automaton = automaton.advance_to(self.generation);
+
+        if automaton.matrix[i][j] == CellState::Alive {
+            return Err(format!(
+                "Start ({}, {}) is alive at generation {}",
+                i, j, self.generation
+            )
+            .into());
+        }
+
+        automaton.starting_point = self.position;
+
+        Ok(automaton)
     }
+}
 
-    let start_time = Instant::now();
-    let result = path_finder(automaton.clone());
-    let end_time = Instant::now();
-    println!("Time elapsed: {:?}", end_time.duration_since(start_time));
-
-    match result {
-        Some(result) => {
-            if verify_result(&automaton, &result) {
-                return Some(result);
-            } else {
-                return None;
-            }
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub enum Solver {
+    #[default]
+    AStar,
+    Anytime,
+    Beam(BeamOptions),
+    Bfs,
+    Bidirectional,
+    Original(DfsOptions),
+    /// Forward BFS spread over this many threads.
+    Parallel(usize),
+    /// A* that skips the states from which the finish cannot be reached.
+    Pruned,
+}
+
+impl FromStr for Solver {
+    type Err = String;
+
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        match name {
+            "astar" => Ok(Solver::AStar),
+            "anytime" => Ok(Solver::Anytime),
+            "beam" => Ok(Solver::Beam(BeamOptions::default())),
+            "bfs" => Ok(Solver::Bfs),
+            "bidirectional" => Ok(Solver::Bidirectional),
+            "original" => Ok(Solver::Original(DfsOptions::default())),
+            "parallel" => Ok(Solver::Parallel(DEFAULT_THREADS)),
+            "pruned" => Ok(Solver::Pruned),
+            _ => Err(format!("Unknown solver: {}", name)),
         }
-        None => None,
     }
 }
 
-pub fn verify_result(automaton: &Automaton, result: &Vec<Position>) -> bool {
-    let mut automaton = automaton.clone();
+pub fn find_path(automaton: Automaton, solver: Solver) -> Option<Vec<Position>> {
+    find_path_with_observer(
+        automaton,
+        solver,
+        SearchLimits::default(),
+        &Interrupt::new(),
+        &mut LogObserver::default(),
+    )
+    .path()
+}
 
-    let verification_result = result.clone();
+/// Same as `find_path`, reporting the progress of the search to `observer`.
+/// Every verified path found along the way is reported as an improvement as
+/// soon as it is found. Only the anytime and depth first solvers find more
+/// than one; the others report their single path when they finish.
+/// Triggering `interrupt` or running out of any of the `limits` stops the
+/// search, which then reports the closest it got to the finish.
+pub fn find_path_with_observer(
+    automaton: Automaton,
+    solver: Solver,
+    limits: SearchLimits,
+    interrupt: &Interrupt,
+    observer: &mut dyn SearchObserver,
+) -> SearchOutcome {
+    search(&automaton, solver, limits, interrupt, observer).outcome
+}
 
-    for current_position in verification_result {
-        let (i, j) = current_position;
-        if automaton.matrix[i][j] == CellState::Alive {
-            println!(
-                "Current position {:?} coincids with alive cell at iteration {}",
-                current_position, automaton.generation
-            );
+fn search(
+    automaton: &Automaton,
+    solver: Solver,
+    limits: SearchLimits,
+    interrupt: &Interrupt,
+    observer: &mut dyn SearchObserver,
+) -> SolveResult {
+    let mut context = SearchContext::new(limits, interrupt, observer);
+
+    let outcome = match solver {
+        Solver::AStar => astar::path_finder(automaton.clone(), &mut context),
+        Solver::Anytime => anytime::path_finder(automaton.clone(), &mut context),
+        Solver::Beam(options) => beam::path_finder(automaton.clone(), options, &mut context),
+        Solver::Bfs => bfs::path_finder(automaton.clone(), &mut context),
+        Solver::Bidirectional => bidirectional::path_finder(automaton.clone(), &mut context),
+        Solver::Original(options) => {
+            original::path_finder(automaton.clone(), options, &mut context)
+        }
+        Solver::Parallel(threads) => {
+            bfs::path_finder_with_threads(automaton.clone(), threads, &mut context)
+        }
+        Solver::Pruned => astar::pruned_path_finder(automaton.clone(), &mut context),
+    };
+
+    let reports_improvements = matches!(solver, Solver::Anytime | Solver::Original(_));
+
+    finish(automaton, outcome, context, !reports_improvements)
+}
 
-            return false;
+/// A* search that is saved periodically and can be resumed after a crash or
+/// an interruption, see `Checkpointing`. Fails if the checkpoint to resume
+/// from is missing, unreadable or was saved for another board.
+pub fn find_path_with_checkpoint(
+    automaton: Automaton,
+    checkpointing: &Checkpointing,
+    limits: SearchLimits,
+    interrupt: &Interrupt,
+    observer: &mut dyn SearchObserver,
+) -> Result<SearchOutcome, Box<dyn Error>> {
+    let mut context = SearchContext::new(limits, interrupt, observer);
+
+    let outcome =
+        astar::path_finder_with_checkpoint(automaton.clone(), checkpointing, &mut context)?;
+
+    Ok(finish(&automaton, outcome, context, true).outcome)
+}
+
+/// Verifies the outcome of a solver and hands it to the observer.
+fn finish(
+    automaton: &Automaton,
+    outcome: SearchOutcome,
+    mut context: SearchContext,
+    report_improvement: bool,
+) -> SolveResult {
+    let (outcome, verification) = context.verify(automaton, outcome);
+
+    if let SearchOutcome::Found(path) = &outcome {
+        if report_improvement {
+            context.improvement(path);
         }
+    }
 
-        automaton = automaton.iterate();
+    let stats = context.finish(&outcome);
+
+    SolveResult {
+        outcome,
+        verification,
+        stats,
     }
+}
 
-    true
+/// Whether `result` is a valid path through `automaton`, see `verify_path`.
+pub fn verify_result(automaton: &Automaton, result: &[Position]) -> bool {
+    verify_path(automaton, result).is_valid()
 }
@@ -0,0 +1,95 @@
+use crate::automaton::{Automaton, Position};
+use crate::path_finder::astar::{algorithm, UNWEIGHTED};
+use crate::path_finder::context::SearchContext;
+use crate::path_finder::outcome::{Limit, PartialPath, SearchOutcome, Stop};
+use crate::path_finder::shared::manhattan_distance;
+use crate::path_finder::verify_result;
+
+/// Heuristic weights, in tenths, tried in order. The greedier searches find
+/// a path quickly and every following one only looks for shorter paths.
+const WEIGHTS: [u32; 6] = [50, 30, 20, 15, 12, UNWEIGHTED];
+
+/// Weighted A* with a decreasing weight.
+///
+/// Each time a shorter path is found it is verified and reported as an
+/// improvement, so the caller always holds the best path so far even if
+/// the run never gets to the final, optimal search. When interrupted, the
+/// best path so far is reported as the partial path. The budget's expanded
+/// states limit applies to each pass on its own.
+pub fn path_finder(automaton: Automaton, context: &mut SearchContext) -> SearchOutcome {
+    let automaton_map = context.automaton_map(automaton.clone(), 1);
+
+    let shortest_possible =
+        1 + manhattan_distance(automaton.starting_point, automaton.finishing_point) as usize;
+
+    let mut best: Option<Vec<Position>> = None;
+    let mut generation_limit_partial: Option<PartialPath> = None;
+
+    for weight in WEIGHTS {
+        // Generation at which the best path so far arrives
+        let arrival_bound = best
+            .as_ref()
+            .map(|path| automaton.generation + path.len() as u32 - 1);
+
+        context.new_pass();
+        context.heuristic_weight(weight);
+
+        let path = match algorithm(&automaton, &automaton_map, weight, arrival_bound, context) {
+            SearchOutcome::Found(path) => path,
+            SearchOutcome::NoPath => continue,
+            SearchOutcome::LimitExceeded {
+                which: Limit::MaxGeneration,
+                partial,
+            } => {
+                // A less greedy pass may still get there in time
+                generation_limit_partial = generation_limit_partial.or(partial);
+                continue;
+            }
+            SearchOutcome::LimitExceeded { which, partial } => {
+                let partial = best_partial_path(&automaton, best, partial);
+
+                return Stop::LimitExceeded(which).outcome(partial);
+            }
+            SearchOutcome::Interrupted(partial) => {
+                let partial = best_partial_path(&automaton, best, partial);
+
+                return Stop::Interrupted.outcome(partial);
+            }
+        };
+
+        if !verify_result(&automaton, &path) {
+            continue;
+        }
+
+        context.improvement(&path);
+
+        let is_optimal = path.len() == shortest_possible;
+        best = Some(path);
+
+        if is_optimal {
+            break;
+        }
+    }
+
+    match (best, generation_limit_partial) {
+        (Some(path), _) => SearchOutcome::Found(path),
+        (None, Some(partial)) => Stop::LimitExceeded(Limit::MaxGeneration).outcome(Some(partial)),
+        (None, None) => SearchOutcome::NoPath,
+    }
+}
+
+/// The best path found so far, or else the closest the last pass got.
+fn best_partial_path(
+    automaton: &Automaton,
+    best: Option<Vec<Position>>,
+    partial: Option<PartialPath>,
+) -> Option<PartialPath> {
+    match best {
+        Some(path) => Some(PartialPath::new(
+            path,
+            automaton.generation,
+            automaton.finishing_point,
+        )),
+        None => partial,
+    }
+}
@@ -1,86 +1,244 @@
-use crate::path_finder::shared::{get_possible_moves, manhattan_distance};
-use std::{
-    collections::{HashMap, HashSet},
-    vec,
-};
+use crate::path_finder::context::SearchContext;
+use crate::path_finder::dead_ends::LiveStates;
+use crate::path_finder::outcome::{ClosestState, PartialPath, SearchOutcome};
+use crate::path_finder::shared::{get_possible_moves, manhattan_distance, Direction};
+use serde::{Deserialize, Serialize};
+use std::error::Error;
+use std::vec;
 
 pub mod automaton_map;
+pub mod checkpoint;
 mod priority_queue;
+mod state_store;
 
 use crate::path_finder::astar::priority_queue::PriorityQueue;
 
-use crate::config::REPORTING_ITERATION_STEP;
-
 use self::automaton_map::InMemoryAutomatonMap;
 use crate::automaton::{Automaton, Position};
 
+use self::checkpoint::Checkpointing;
 use self::priority_queue::Node;
+use self::state_store::StateStore;
 
-pub fn path_finder(automaton: Automaton) -> Option<Vec<Position>> {
-    let starting_point = automaton.starting_point;
-    let ending_point = automaton.finishing_point;
+/// Heuristic weight, in tenths, that keeps A* optimal.
+pub const UNWEIGHTED: u32 = 10;
 
-    let result = algorithm(automaton, starting_point, ending_point);
+pub fn path_finder(automaton: Automaton, context: &mut SearchContext) -> SearchOutcome {
+    let automaton_map = context.automaton_map(automaton.clone(), 1);
 
-    result
+    algorithm(&automaton, &automaton_map, UNWEIGHTED, None, context)
 }
 
-fn algorithm(
-    automaton: Automaton,
-    starting_point: Position,
-    ending_point: Position,
-) -> Option<Vec<Position>> {
-    let mut closed_states: HashSet<State> = HashSet::new();
-    let mut results: HashMap<State, Result> = HashMap::new();
-    let mut priority_queue = PriorityQueue::new();
+/// Same as `path_finder`, never queuing the states from which the finish
+/// cannot be reached by the last precomputed generation, see `LiveStates`.
+///
+/// The live states ignore the `max_generation` limit, so a pruned state could
+/// not have led to a path at all, and running out of states means there is
+/// none, as for `path_finder`.
+pub fn pruned_path_finder(automaton: Automaton, context: &mut SearchContext) -> SearchOutcome {
+    let automaton_map = context.automaton_map(automaton.clone(), 1);
+
+    let live_states = LiveStates::new(
+        &automaton_map,
+        automaton.finishing_point,
+        automaton_map.generations(),
+    );
+    context.pruning(live_states.stats());
+
+    let search = Search::new(&automaton, UNWEIGHTED, None);
+
+    run(
+        &automaton,
+        &automaton_map,
+        search,
+        None,
+        Some(&live_states),
+        context,
+    )
+}
 
-    let automaton_map = InMemoryAutomatonMap::new(automaton.clone());
+/// A* over (position, generation) states.
+///
+/// `weight` multiplies the heuristic, in tenths: anything above `UNWEIGHTED`
+/// trades optimality for fewer expansions. States that cannot reach the finish
+/// before the `arrival_bound` generation are never queued.
+pub fn algorithm(
+    automaton: &Automaton,
+    automaton_map: &InMemoryAutomatonMap,
+    weight: u32,
+    arrival_bound: Option<u32>,
+    context: &mut SearchContext,
+) -> SearchOutcome {
+    let search = Search::new(automaton, weight, arrival_bound);
+
+    run(automaton, automaton_map, search, None, None, context)
+}
 
-    let starting_state = State {
-        automaton_generation: 1,
-        position: starting_point,
+/// Same as `path_finder`, but the search is saved to a checkpoint file every
+/// `checkpointing.interval` iterations and when interrupted, and picks up from
+/// that file instead of starting over when `checkpointing.resume` is set.
+///
+/// Fails without searching if the checkpoint cannot be loaded.
+pub fn path_finder_with_checkpoint(
+    automaton: Automaton,
+    checkpointing: &Checkpointing,
+    context: &mut SearchContext,
+) -> Result<SearchOutcome, Box<dyn Error>> {
+    let search = if checkpointing.resume {
+        let search = checkpoint::load(&checkpointing.path, &automaton)
+            .map_err(|error| format!("Error loading {}: {}", checkpointing.path, error))?;
+
+        context.resume(search.counter);
+
+        search
+    } else {
+        Search::new(&automaton, UNWEIGHTED, None)
     };
 
-    let starting_result = Result {
-        cost: calculate_cost(
-            starting_state.automaton_generation,
-            manhattan_distance(ending_point, starting_point),
-        ),
-        previous_position: starting_point,
-    };
+    let automaton_map = context.automaton_map(automaton.clone(), 1);
 
-    results.insert(starting_state, starting_result);
+    Ok(run(
+        &automaton,
+        &automaton_map,
+        search,
+        Some(checkpointing),
+        None,
+        context,
+    ))
+}
 
-    priority_queue.enqueue(Node {
-        state: starting_state,
-        cost: starting_result.cost,
-    });
+/// Everything the A* loop needs to carry on from where it stopped.
+#[derive(Serialize, Deserialize)]
+pub struct Search {
+    states: StateStore,
+    priority_queue: PriorityQueue,
+    starting_state: State,
+    counter: u64,
+    weight: u32,
+    arrival_bound: Option<u32>,
+}
 
-    let mut counter: u64 = 0;
+impl Search {
+    fn new(automaton: &Automaton, weight: u32, arrival_bound: Option<u32>) -> Self {
+        let starting_point = automaton.starting_point;
+        let ending_point = automaton.finishing_point;
 
-    while let Some(current) = priority_queue.dequeue() {
-        let current_node = current;
+        let mut states = StateStore::new(automaton.matrix.len(), automaton.matrix[0].len());
+        let mut priority_queue = PriorityQueue::new();
+
+        let starting_state = State {
+            automaton_generation: automaton.generation,
+            position: starting_point,
+        };
+
+        states.insert(starting_state, None);
 
-        if counter % REPORTING_ITERATION_STEP == 0 {
-            let current_result = results.get(&current_node).unwrap();
-            println!("Current iteration: {:?}", counter);
-            println!("Current result: {:?}", current_result);
-            println!("Results length {:?}", results.len());
-            println!("Queue length {:?}", priority_queue.get_length());
+        priority_queue.enqueue(Node {
+            state: starting_state,
+            cost: calculate_cost(
+                starting_state.automaton_generation,
+                manhattan_distance(ending_point, starting_point),
+                weight,
+            ),
+        });
+
+        Search {
+            states,
+            priority_queue,
+            starting_state,
+            counter: 0,
+            weight,
+            arrival_bound,
         }
+    }
+}
+
+fn run(
+    automaton: &Automaton,
+    automaton_map: &InMemoryAutomatonMap,
+    search: Search,
+    checkpointing: Option<&Checkpointing>,
+    live_states: Option<&LiveStates>,
+    context: &mut SearchContext,
+) -> SearchOutcome {
+    let ending_point = automaton.finishing_point;
+
+    let mut search = search;
+    let mut closest_state: ClosestState<State> = ClosestState::default();
+
+    let max_generation = context.max_generation(automaton_map.generations());
+    let mut has_reached_max_generation = false;
+
+    while let Some(current) = search.priority_queue.dequeue() {
+        let memory_usage = automaton_map.memory_usage()
+            + live_states.map_or(0, LiveStates::memory_usage)
+            + search.states.memory_usage()
+            + search.priority_queue.memory_usage();
+
+        let stop = context.check(
+            search.counter,
+            current.automaton_generation,
+            search.priority_queue.get_length(),
+            memory_usage,
+        );
+
+        if let Some(stop) = stop {
+            // Put the state back so the checkpoint holds the whole open list
+            search.priority_queue.enqueue(Node {
+                state: current,
+                cost: calculate_cost(
+                    current.automaton_generation,
+                    manhattan_distance(ending_point, current.position),
+                    search.weight,
+                ),
+            });
+
+            if let Some(checkpointing) = checkpointing {
+                if let Err(error) = checkpoint::save(&checkpointing.path, automaton, &search) {
+                    context.checkpoint_error(&*error);
+                }
+            }
+
+            let partial = closest_partial_path(&search, &closest_state, ending_point);
+
+            return stop.outcome(partial);
+        }
+
+        let Search {
+            states,
+            priority_queue,
+            starting_state,
+            counter,
+            weight,
+            arrival_bound,
+        } = &mut search;
+
+        let current_node = current;
 
         if current_node.position == ending_point {
-            return Some(recreate_steps(&results, &current_node));
+            return SearchOutcome::Found(recreate_steps(states, starting_state, &current_node));
         }
 
+        closest_state.offer(
+            manhattan_distance(ending_point, current_node.position),
+            current_node.automaton_generation,
+            current_node,
+        );
+
         let current_position = current_node.position;
 
         let next_generation = current_node.automaton_generation + 1;
 
+        if next_generation > max_generation {
+            has_reached_max_generation = true;
+            states.close(current_node);
+            continue;
+        }
+
         let next_gen_automaton_matrix =
             automaton_map.get_automaton_matrix_generation(next_generation);
 
-        let possible_moves = get_possible_moves(&next_gen_automaton_matrix, current_position);
+        let possible_moves = get_possible_moves(next_gen_automaton_matrix, current_position);
 
         for position in possible_moves {
             let new_state = State {
@@ -88,87 +246,102 @@ fn algorithm(
                 position,
             };
 
-            if closed_states.contains(&new_state) {
+            if states.is_closed(&new_state) {
                 continue;
             }
 
-            let calculated_cost =
-                calculate_cost(next_generation, manhattan_distance(ending_point, position));
+            if live_states
+                .is_some_and(|live_states| !live_states.contains(next_generation, position))
+            {
+                continue;
+            }
 
-            let calculated_result = Result {
-                previous_position: current_node.position,
-                cost: calculated_cost,
-            };
+            let distance = manhattan_distance(ending_point, position);
+
+            if let Some(bound) = arrival_bound {
+                if next_generation + distance >= *bound {
+                    continue;
+                }
+            }
+
+            // The cost of a state only depends on its generation and position,
+            // so a state that was already seen can never be reached any cheaper.
+            if !states.insert(
+                new_state,
+                Some(Direction::between(current_position, position)),
+            ) {
+                continue;
+            }
+
+            priority_queue.enqueue(Node {
+                state: new_state,
+                cost: calculate_cost(next_generation, distance, *weight),
+            })
+        }
+
+        states.close(current_node);
 
-            if results.contains_key(&new_state) {
-                let past_result = results.get(&new_state).unwrap();
+        *counter += 1;
 
-                if calculated_cost < past_result.cost {
-                    results.insert(new_state, calculated_result);
-                    priority_queue.update(Node {
-                        state: new_state,
-                        cost: calculated_cost,
-                    })
+        if let Some(checkpointing) = checkpointing {
+            if search.counter.is_multiple_of(checkpointing.interval) {
+                if let Err(error) = checkpoint::save(&checkpointing.path, automaton, &search) {
+                    context.checkpoint_error(&*error);
                 }
-            } else {
-                results.insert(new_state, calculated_result);
-                priority_queue.enqueue(Node {
-                    state: new_state,
-                    cost: calculated_cost,
-                })
             }
         }
+    }
 
-        closed_states.insert(State {
-            automaton_generation: current_node.automaton_generation,
-            position: current_node.position,
-        });
+    if has_reached_max_generation {
+        let partial = closest_partial_path(&search, &closest_state, ending_point);
 
-        counter += 1;
+        return context.horizon_outcome(automaton_map.generations(), partial);
     }
 
-    None
+    SearchOutcome::NoPath
 }
 
-fn calculate_cost(starting_node_cost: u32, heuristic_cost: u32) -> u32 {
-    starting_node_cost + heuristic_cost
+fn closest_partial_path(
+    search: &Search,
+    closest_state: &ClosestState<State>,
+    ending_point: Position,
+) -> Option<PartialPath> {
+    closest_state.get().map(|state| {
+        PartialPath::new(
+            recreate_steps(&search.states, &search.starting_state, state),
+            search.starting_state.automaton_generation,
+            ending_point,
+        )
+    })
 }
 
-#[derive(PartialEq, Eq, Hash, Clone, Copy, Debug)]
+fn calculate_cost(starting_node_cost: u32, heuristic_cost: u32, weight: u32) -> u32 {
+    starting_node_cost * UNWEIGHTED + heuristic_cost * weight
+}
+
+#[derive(PartialEq, Eq, Hash, Clone, Copy, Debug, Serialize, Deserialize)]
 pub struct State {
     position: Position,
     automaton_generation: u32,
 }
 
-fn recreate_steps(results: &HashMap<State, Result>, entry: &State) -> Vec<Position> {
+fn recreate_steps(states: &StateStore, starting_state: &State, entry: &State) -> Vec<Position> {
     let mut state = entry.to_owned();
-    let mut result = results.get(&state).unwrap().clone();
-
-    let mut path: Vec<Position> = vec![];
 
-    path.push(state.position);
+    let mut path: Vec<Position> = vec![state.position];
 
-    println!("Made it till the end, recreating steps...");
+    while state != *starting_state {
+        let direction = states.parent_direction(&state);
 
-    while let Some(previous_result) = results.get(&State {
-        automaton_generation: state.automaton_generation - 1,
-        position: result.previous_position,
-    }) {
-        path.push(result.previous_position);
-        result = previous_result.to_owned();
         state = State {
             automaton_generation: state.automaton_generation - 1,
-            position: result.previous_position,
+            position: direction.origin(state.position),
         };
+
+        path.push(state.position);
     }
 
     path.reverse();
 
     path
 }
-
-#[derive(Clone, Copy, Debug)]
-pub struct Result {
-    cost: u32,
-    previous_position: Position,
-}
@@ -1,6 +1,6 @@
 use std::{collections::HashMap, fs};
 
-use crate::automaton::{self, Automaton, CellState};
+use crate::automaton::{Automaton, CellState};
 use crate::config::{AUTOMATON_SNAPSHOTS_DIRECTORY, GENERATION_STEP};
 
 pub trait AutomatonMap {
@@ -52,13 +52,39 @@ impl SnapshotAutomatonMap {
 
 pub struct InMemoryAutomatonMap {
     automaton_map: HashMap<u32, Vec<Vec<CellState>>>,
+    first_generation: u32,
+    // The generations never change once computed, so neither does their size
+    memory_bytes: usize,
 }
 
 impl InMemoryAutomatonMap {
     pub fn new(automaton: Automaton) -> Self {
-        let automaton_map = get_automaton_map(&automaton);
+        Self::with_threads(automaton, 1)
+    }
+
+    /// Same as `new`, computing each generation on `threads` threads.
+    pub fn with_threads(automaton: Automaton, threads: usize) -> Self {
+        Self::with_progress(automaton, threads, &mut |_| {})
+    }
 
-        Self { automaton_map }
+    /// Same as `with_threads`, calling `on_generation` after each generation.
+    pub fn with_progress(
+        automaton: Automaton,
+        threads: usize,
+        on_generation: &mut dyn FnMut(u32),
+    ) -> Self {
+        let automaton_map = get_automaton_map(&automaton, threads, on_generation);
+
+        let memory_bytes = automaton_map
+            .values()
+            .map(|matrix| matrix.len() * (matrix[0].len() + size_of::<Vec<CellState>>()))
+            .sum();
+
+        Self {
+            automaton_map,
+            first_generation: automaton.generation,
+            memory_bytes,
+        }
     }
 
     pub fn get_automaton_matrix_generation(&self, generation: u32) -> &Vec<Vec<CellState>> {
@@ -67,27 +93,48 @@ impl InMemoryAutomatonMap {
             None => panic!("No automaton matrix for generation {}", generation),
         }
     }
+
+    /// Generation of the automaton the map was built from.
+    pub fn first_generation(&self) -> u32 {
+        self.first_generation
+    }
+
+    /// Last generation held in memory.
+    pub fn generations(&self) -> u32 {
+        self.first_generation + self.automaton_map.len() as u32 - 1
+    }
+
+    /// Approximate number of bytes held by the precomputed generations.
+    pub fn memory_usage(&self) -> usize {
+        self.memory_bytes
+    }
 }
 
-fn get_automaton_map(automaton: &Automaton) -> HashMap<u32, Vec<Vec<CellState>>> {
+fn get_automaton_map(
+    automaton: &Automaton,
+    threads: usize,
+    on_generation: &mut dyn FnMut(u32),
+) -> HashMap<u32, Vec<Vec<CellState>>> {
     let mut map: HashMap<u32, Vec<Vec<CellState>>> = HashMap::new();
 
     let generations = 2.5 * (automaton.matrix.len() + automaton.matrix[0].len()) as f32;
 
-    println!("Generating {} automaton maps", generations);
-
     let mut automaton_clone = automaton.clone();
 
-    for i in 1..=generations as u32 {
-        map.insert(i, automaton_clone.matrix.clone());
-        automaton_clone = automaton_clone.iterate();
+    for _ in 0..generations as u32 {
+        let generation = automaton_clone.generation;
+        map.insert(generation, automaton_clone.matrix.clone());
 
-        if i % 100 == 0 {
-            println!("Generated {} automaton maps", i);
-        }
+        automaton_clone = if threads > 1 {
+            automaton_clone.iterate_parallel(threads)
+        } else {
+            automaton_clone.iterate()
+        };
+
+        on_generation(generation);
     }
 
-    return map;
+    map
 }
 
 fn get_snapshots_from_files() -> HashMap<u32, Vec<Vec<CellState>>> {
@@ -0,0 +1,96 @@
+use std::error::Error;
+use std::fs::{self, File};
+use std::io::{BufReader, BufWriter};
+
+use serde::{Deserialize, Serialize};
+
+use super::Search;
+use crate::automaton::{Automaton, Position};
+
+/// Where and how often the A* search is saved, and whether to pick up from
+/// an existing checkpoint.
+#[derive(Clone, Debug)]
+pub struct Checkpointing {
+    pub path: String,
+    pub interval: u64,
+    pub resume: bool,
+}
+
+/// Identifies the board a search was started on, so a checkpoint is never
+/// resumed against another one.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Fingerprint {
+    height: usize,
+    width: usize,
+    starting_point: Position,
+    finishing_point: Position,
+    generation: u32,
+    /// FNV-1a of the cells, which unlike the standard library hasher gives
+    /// the same value on every build.
+    cells_hash: u64,
+}
+
+impl Fingerprint {
+    pub fn of(automaton: &Automaton) -> Self {
+        let mut cells_hash: u64 = 0xcbf29ce484222325;
+
+        for value in automaton.to_integer_matrix().iter().flatten() {
+            cells_hash ^= *value as u64;
+            cells_hash = cells_hash.wrapping_mul(0x100000001b3);
+        }
+
+        Fingerprint {
+            height: automaton.matrix.len(),
+            width: automaton.matrix[0].len(),
+            starting_point: automaton.starting_point,
+            finishing_point: automaton.finishing_point,
+            generation: automaton.generation,
+            cells_hash,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+struct Checkpoint<S> {
+    input: Fingerprint,
+    search: S,
+}
+
+/// Writes the search to a temporary file first and then moves it over the
+/// checkpoint, so a crash while saving never leaves a truncated checkpoint.
+/// The file is flushed and synced before the move, so a failed write leaves
+/// the previous checkpoint in place.
+pub fn save(path: &str, automaton: &Automaton, search: &Search) -> Result<(), Box<dyn Error>> {
+    let temporary_path = format!("{}.tmp", path);
+
+    let checkpoint = Checkpoint {
+        input: Fingerprint::of(automaton),
+        search,
+    };
+
+    let mut writer = BufWriter::new(File::create(&temporary_path)?);
+    serde_json::to_writer(&mut writer, &checkpoint)?;
+    writer.into_inner()?.sync_all()?;
+
+    fs::rename(temporary_path, path)?;
+
+    Ok(())
+}
+
+/// Fails if the checkpoint was saved for another board than `automaton`.
+pub fn load(path: &str, automaton: &Automaton) -> Result<Search, Box<dyn Error>> {
+    let file = File::open(path)?;
+    let checkpoint: Checkpoint<Search> = serde_json::from_reader(BufReader::new(file))?;
+
+    let input = Fingerprint::of(automaton);
+
+    if checkpoint.input != input {
+        return Err(format!(
+            "Checkpoint was saved for another input: {:?}, not {:?}",
+            checkpoint.input, input
+        )
+        .into());
+    }
+
+    Ok(checkpoint.search)
+}
@@ -1,6 +1,5 @@
 use crate::path_finder::astar::State;
-use std::cmp::Ordering;
-use std::collections::BinaryHeap;
+use serde::{Deserialize, Serialize};
 
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub struct Node {
@@ -8,43 +7,62 @@ pub struct Node {
     pub cost: u32,
 }
 
-impl Ord for Node {
-    fn cmp(&self, other: &Self) -> Ordering {
-        other.cost.cmp(&self.cost)
-    }
-}
-
-impl PartialOrd for Node {
-    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
-        Some(self.cmp(other))
-    }
-}
-
+/// Bucket queue: costs are small integers (generation + distance to the finish),
+/// so every cost gets its own bucket, which makes enqueue and dequeue O(1)
+/// amortized.
+///
+/// There is no decrease-key: the cost of a state only depends on its generation
+/// and position, and the search never queues a state twice.
+#[derive(Serialize, Deserialize)]
 pub struct PriorityQueue {
-    items: BinaryHeap<Node>,
+    buckets: Vec<Vec<State>>,
+    minimum_cost: usize,
+    length: usize,
 }
 
 impl PriorityQueue {
     pub fn new() -> Self {
         PriorityQueue {
-            items: BinaryHeap::new(),
+            buckets: Vec::new(),
+            minimum_cost: 0,
+            length: 0,
         }
     }
 
     pub fn enqueue(&mut self, node: Node) {
-        self.items.push(node);
+        let cost = node.cost as usize;
+
+        if cost >= self.buckets.len() {
+            self.buckets.resize_with(cost + 1, Vec::new);
+        }
+
+        self.buckets[cost].push(node.state);
+        self.length += 1;
+
+        if cost < self.minimum_cost {
+            self.minimum_cost = cost;
+        }
     }
 
     pub fn dequeue(&mut self) -> Option<State> {
-        self.items.pop().map(|node| node.state)
-    }
+        while self.minimum_cost < self.buckets.len() {
+            if let Some(state) = self.buckets[self.minimum_cost].pop() {
+                self.length -= 1;
+                return Some(state);
+            }
 
-    pub fn update(&mut self, node: Node) {
-        self.items.retain(|item| node.state != item.state);
-        self.enqueue(node);
+            self.minimum_cost += 1;
+        }
+
+        None
     }
 
     pub fn get_length(&self) -> usize {
-        self.items.len()
+        self.length
+    }
+
+    /// Approximate number of bytes held by the queued states.
+    pub fn memory_usage(&self) -> usize {
+        self.buckets.len() * size_of::<Vec<State>>() + self.length * size_of::<State>()
     }
 }
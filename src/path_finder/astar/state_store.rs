@@ -0,0 +1,250 @@
+use crate::automaton::Position;
+use crate::path_finder::shared::Direction;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use super::State;
+
+const BITS_PER_WORD: usize = 64;
+const DIRECTION_BITS: usize = 2;
+const DIRECTIONS_PER_WORD: usize = BITS_PER_WORD / DIRECTION_BITS;
+
+/// Dense replacement for hashing every (position, generation) pair.
+///
+/// Each generation that the search touches gets a layer with one bit per cell
+/// for "seen" (queued or expanded), one bit per cell for "closed" and a 2 bit
+/// code with the direction the particle moved to reach the cell. Layers are
+/// only allocated once a state in that generation is seen.
+pub struct StateStore {
+    height: usize,
+    width: usize,
+    layers: Vec<Option<Layer>>,
+    allocated_bytes: usize,
+}
+
+struct Layer {
+    seen: Vec<u64>,
+    closed: Vec<u64>,
+    parents: Vec<u64>,
+}
+
+impl Layer {
+    fn new(cells: usize) -> Self {
+        let bit_words = cells.div_ceil(BITS_PER_WORD);
+        let parent_words = cells.div_ceil(DIRECTIONS_PER_WORD);
+
+        Layer {
+            seen: vec![0; bit_words],
+            closed: vec![0; bit_words],
+            parents: vec![0; parent_words],
+        }
+    }
+
+    fn memory_usage(&self) -> usize {
+        (self.seen.len() + self.closed.len() + self.parents.len()) * size_of::<u64>()
+    }
+}
+
+impl StateStore {
+    pub fn new(height: usize, width: usize) -> Self {
+        StateStore {
+            height,
+            width,
+            layers: Vec::new(),
+            allocated_bytes: 0,
+        }
+    }
+
+    /// Marks a state as seen, recording the direction of the move that reached it.
+    /// Returns false if the state had already been seen.
+    pub fn insert(&mut self, state: State, parent: Option<Direction>) -> bool {
+        let cell = self.cell(state.position);
+        let layer = self.layer_mut(state.automaton_generation);
+
+        if get_bit(&layer.seen, cell) {
+            return false;
+        }
+
+        set_bit(&mut layer.seen, cell);
+
+        if let Some(direction) = parent {
+            let word = cell / DIRECTIONS_PER_WORD;
+            let shift = (cell % DIRECTIONS_PER_WORD) * DIRECTION_BITS;
+            layer.parents[word] &= !(0b11 << shift);
+            layer.parents[word] |= direction.code() << shift;
+        }
+
+        true
+    }
+
+    pub fn close(&mut self, state: State) {
+        let cell = self.cell(state.position);
+        let layer = self.layer_mut(state.automaton_generation);
+
+        set_bit(&mut layer.closed, cell);
+    }
+
+    pub fn is_closed(&self, state: &State) -> bool {
+        match self.layer(state.automaton_generation) {
+            Some(layer) => get_bit(&layer.closed, self.cell(state.position)),
+            None => false,
+        }
+    }
+
+    /// Direction of the move that reached a seen state.
+    pub fn parent_direction(&self, state: &State) -> Direction {
+        let layer = match self.layer(state.automaton_generation) {
+            Some(layer) => layer,
+            None => panic!("State {:?} was never seen", state),
+        };
+
+        let cell = self.cell(state.position);
+        let word = layer.parents[cell / DIRECTIONS_PER_WORD];
+        let shift = (cell % DIRECTIONS_PER_WORD) * DIRECTION_BITS;
+
+        Direction::from_code(word >> shift)
+    }
+
+    /// Approximate number of bytes held by the allocated layers.
+    pub fn memory_usage(&self) -> usize {
+        self.allocated_bytes
+    }
+
+    fn cell(&self, (i, j): Position) -> usize {
+        i * self.width + j
+    }
+
+    fn layer(&self, generation: u32) -> Option<&Layer> {
+        self.layers
+            .get(generation as usize)
+            .and_then(|layer| layer.as_ref())
+    }
+
+    fn layer_mut(&mut self, generation: u32) -> &mut Layer {
+        let index = generation as usize;
+
+        if index >= self.layers.len() {
+            self.layers.resize_with(index + 1, || None);
+        }
+
+        if self.layers[index].is_none() {
+            let layer = Layer::new(self.height * self.width);
+            self.allocated_bytes += layer.memory_usage();
+            self.layers[index] = Some(layer);
+        }
+
+        self.layers[index].as_mut().unwrap()
+    }
+}
+
+/// How a `StateStore` is saved in checkpoints: the dense layers are mostly
+/// empty on large boards, so only the seen states are kept, each packed in a
+/// single number as its cell, whether it is closed and the direction that
+/// reached it. Cells are stored as the gap from the previous seen cell of the
+/// layer, which keeps the numbers short.
+#[derive(Serialize, Deserialize)]
+struct SeenStates {
+    height: usize,
+    width: usize,
+    // (generation, packed states)
+    layers: Vec<(u32, Vec<u64>)>,
+}
+
+const CLOSED_BIT: u64 = 1 << DIRECTION_BITS;
+const CELL_SHIFT: usize = DIRECTION_BITS + 1;
+
+impl Serialize for StateStore {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut layers = vec![];
+
+        for (generation, layer) in self.layers.iter().enumerate() {
+            let Some(layer) = layer else {
+                continue;
+            };
+
+            let mut states = vec![];
+            let mut previous_cell = 0;
+
+            let seen_cells = layer.seen.iter().enumerate().flat_map(|(index, word)| {
+                let mut bits = *word;
+
+                std::iter::from_fn(move || {
+                    let bit = bits.trailing_zeros() as usize;
+                    bits &= bits.checked_sub(1)?;
+
+                    Some(index * BITS_PER_WORD + bit)
+                })
+            });
+
+            for cell in seen_cells {
+                let word = layer.parents[cell / DIRECTIONS_PER_WORD];
+                let direction = (word >> ((cell % DIRECTIONS_PER_WORD) * DIRECTION_BITS)) & 0b11;
+                let closed = if get_bit(&layer.closed, cell) {
+                    CLOSED_BIT
+                } else {
+                    0
+                };
+
+                let gap = (cell - previous_cell) as u64;
+                previous_cell = cell;
+
+                states.push((gap << CELL_SHIFT) | closed | direction);
+            }
+
+            layers.push((generation as u32, states));
+        }
+
+        SeenStates {
+            height: self.height,
+            width: self.width,
+            layers,
+        }
+        .serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for StateStore {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let SeenStates {
+            height,
+            width,
+            layers,
+        } = SeenStates::deserialize(deserializer)?;
+
+        let mut store = StateStore::new(height, width);
+
+        for (generation, states) in layers {
+            let layer = store.layer_mut(generation);
+            let mut cell = 0;
+
+            for state in states {
+                cell += (state >> CELL_SHIFT) as usize;
+
+                if cell >= height * width {
+                    return Err(serde::de::Error::custom(format!(
+                        "Cell {} is outside a {}x{} board",
+                        cell, height, width
+                    )));
+                }
+
+                set_bit(&mut layer.seen, cell);
+
+                if state & CLOSED_BIT != 0 {
+                    set_bit(&mut layer.closed, cell);
+                }
+
+                let shift = (cell % DIRECTIONS_PER_WORD) * DIRECTION_BITS;
+                layer.parents[cell / DIRECTIONS_PER_WORD] |= (state & 0b11) << shift;
+            }
+        }
+
+        Ok(store)
+    }
+}
+
+fn get_bit(words: &[u64], index: usize) -> bool {
+    words[index / BITS_PER_WORD] & (1 << (index % BITS_PER_WORD)) != 0
+}
+
+fn set_bit(words: &mut [u64], index: usize) {
+    words[index / BITS_PER_WORD] |= 1 << (index % BITS_PER_WORD);
+}
@@ -0,0 +1,174 @@
+use std::collections::HashSet;
+
+use crate::automaton::{Automaton, Position};
+use crate::path_finder::context::SearchContext;
+use crate::path_finder::outcome::{ClosestState, PartialPath, SearchOutcome};
+use crate::path_finder::shared::{get_possible_moves, manhattan_distance};
+
+pub const DEFAULT_BEAM_WIDTH: usize = 100;
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct BeamOptions {
+    /// Number of states kept per generation.
+    pub width: usize,
+    /// Side of the square regions the board is split into for diversity: at
+    /// most one state per region is kept. A side of 1 keeps every cell apart,
+    /// which turns diversity off.
+    pub region_size: usize,
+}
+
+impl Default for BeamOptions {
+    fn default() -> Self {
+        BeamOptions {
+            width: DEFAULT_BEAM_WIDTH,
+            region_size: 1,
+        }
+    }
+}
+
+struct BeamState {
+    position: Position,
+    parent: usize,
+}
+
+/// Beam search over generations.
+///
+/// Every generation only the `width` states closest to the finish survive,
+/// so the search is fast but may return a longer path than the optimum, or
+/// none at all if the beam runs into a dead end.
+pub fn path_finder(
+    automaton: Automaton,
+    options: BeamOptions,
+    context: &mut SearchContext,
+) -> SearchOutcome {
+    let starting_point = automaton.starting_point;
+    let finishing_point = automaton.finishing_point;
+
+    let automaton_map = context.automaton_map(automaton, 1);
+    let first_generation = automaton_map.first_generation();
+
+    let region_size = options.region_size.max(1);
+
+    // beams[index] holds generation first_generation + index
+    let mut beams: Vec<Vec<BeamState>> = vec![vec![BeamState {
+        position: starting_point,
+        parent: 0,
+    }]];
+
+    let mut expanded_states: u64 = 0;
+    let mut beam_states: usize = 1;
+
+    for generation in first_generation + 1..=context.max_generation(automaton_map.generations()) {
+        let memory_usage = automaton_map.memory_usage() + beam_states * size_of::<BeamState>();
+
+        let beam_size = beams.last().unwrap().len();
+
+        if let Some(stop) = context.check(expanded_states, generation - 1, beam_size, memory_usage)
+        {
+            let partial = closest_partial_path(&beams, first_generation, finishing_point);
+
+            return stop.outcome(partial);
+        }
+
+        let matrix = automaton_map.get_automaton_matrix_generation(generation);
+        let beam = beams.last().unwrap();
+        expanded_states += beam.len() as u64;
+
+        let mut seen: HashSet<Position> = HashSet::new();
+        let mut candidates: Vec<BeamState> = vec![];
+
+        for (index, state) in beam.iter().enumerate() {
+            for position in get_possible_moves(matrix, state.position) {
+                if seen.insert(position) {
+                    candidates.push(BeamState {
+                        position,
+                        parent: index,
+                    });
+                }
+            }
+        }
+
+        if candidates.is_empty() {
+            return SearchOutcome::NoPath;
+        }
+
+        candidates.sort_by_key(|state| manhattan_distance(finishing_point, state.position));
+
+        let mut regions: HashSet<Position> = HashSet::new();
+        let mut next_beam: Vec<BeamState> = vec![];
+
+        for state in candidates {
+            if next_beam.len() == options.width {
+                break;
+            }
+
+            let region = (
+                state.position.0 / region_size,
+                state.position.1 / region_size,
+            );
+
+            if !regions.insert(region) {
+                continue;
+            }
+
+            let has_arrived = state.position == finishing_point;
+            next_beam.push(state);
+
+            if has_arrived {
+                beams.push(next_beam);
+                let last = beams.last().unwrap().len() - 1;
+
+                return SearchOutcome::Found(recreate_steps(&beams, last));
+            }
+        }
+
+        beam_states += next_beam.len();
+        beams.push(next_beam);
+    }
+
+    let partial = closest_partial_path(&beams, first_generation, finishing_point);
+
+    context.horizon_outcome(automaton_map.generations(), partial)
+}
+
+fn closest_partial_path(
+    beams: &[Vec<BeamState>],
+    first_generation: u32,
+    finishing_point: Position,
+) -> Option<PartialPath> {
+    let mut closest_state: ClosestState<(usize, usize)> = ClosestState::default();
+
+    for (beam_index, beam) in beams.iter().enumerate() {
+        for (index, state) in beam.iter().enumerate() {
+            closest_state.offer(
+                manhattan_distance(finishing_point, state.position),
+                first_generation + beam_index as u32,
+                (beam_index, index),
+            );
+        }
+    }
+
+    closest_state.get().map(|(beam_index, index)| {
+        PartialPath::new(
+            recreate_steps(&beams[..=*beam_index], *index),
+            first_generation,
+            finishing_point,
+        )
+    })
+}
+
+/// Follows parents back from the state at `index` in the last beam.
+fn recreate_steps(beams: &[Vec<BeamState>], index: usize) -> Vec<Position> {
+    let mut path = vec![];
+    let mut index = index;
+
+    for beam in beams.iter().rev() {
+        let state = &beam[index];
+        path.push(state.position);
+        index = state.parent;
+    }
+
+    path.reverse();
+
+    path
+}
@@ -0,0 +1,137 @@
+use crate::automaton::{Automaton, Position};
+use crate::path_finder::astar::automaton_map::InMemoryAutomatonMap;
+use crate::path_finder::context::SearchContext;
+use crate::path_finder::outcome::{ClosestState, PartialPath, SearchOutcome};
+use crate::path_finder::reachability::{adjacent_in, expand_parallel, CellSet};
+use crate::path_finder::shared::manhattan_distance;
+
+/// Forward breadth first search over generations.
+///
+/// Every generation holds the set of cells the particle can be at, so the
+/// first generation whose set contains the finish gives the shortest path.
+pub fn path_finder(automaton: Automaton, context: &mut SearchContext) -> SearchOutcome {
+    path_finder_with_threads(automaton, 1, context)
+}
+
+/// Same search, with every generation of the automaton and every frontier
+/// expansion split between `threads` threads. The layers are the same for
+/// any number of threads, so is the returned path.
+pub fn path_finder_with_threads(
+    automaton: Automaton,
+    threads: usize,
+    context: &mut SearchContext,
+) -> SearchOutcome {
+    let starting_point = automaton.starting_point;
+    let finishing_point = automaton.finishing_point;
+
+    let height = automaton.matrix.len();
+    let width = automaton.matrix[0].len();
+
+    let automaton_map = context.automaton_map(automaton, threads);
+    let first_generation = automaton_map.first_generation();
+
+    let mut first_layer = CellSet::new(height, width);
+    first_layer.insert(starting_point);
+
+    // layers[index] holds generation first_generation + index
+    let mut layers_memory = first_layer.memory_usage();
+    let mut layers = vec![first_layer];
+    let mut expanded_states: u64 = 0;
+
+    for generation in first_generation + 1..=context.max_generation(automaton_map.generations()) {
+        let memory_usage = automaton_map.memory_usage() + layers_memory;
+
+        let frontier_size = layers.last().unwrap().count();
+
+        if let Some(stop) =
+            context.check(expanded_states, generation - 1, frontier_size, memory_usage)
+        {
+            let partial = closest_partial_path(&automaton_map, &layers, finishing_point);
+
+            return stop.outcome(partial);
+        }
+
+        let matrix = automaton_map.get_automaton_matrix_generation(generation);
+        let frontier = layers.last().unwrap();
+        let layer = expand_parallel(matrix, frontier, threads);
+        expanded_states += frontier.count() as u64;
+
+        if layer.is_empty() {
+            return SearchOutcome::NoPath;
+        }
+
+        let has_arrived = layer.contains(finishing_point);
+        layers_memory += layer.memory_usage();
+        layers.push(layer);
+
+        if has_arrived {
+            return SearchOutcome::Found(walk_back(&automaton_map, &layers, finishing_point));
+        }
+    }
+
+    let partial = closest_partial_path(&automaton_map, &layers, finishing_point);
+
+    context.horizon_outcome(automaton_map.generations(), partial)
+}
+
+pub fn layers_memory_usage(layers: &[CellSet]) -> usize {
+    layers.iter().map(|layer| layer.memory_usage()).sum()
+}
+
+/// Path to the reachable cell closest to the finish, in the deepest layer
+/// where that distance is reached. `layers` starts at the first generation of
+/// `automaton_map`.
+pub fn closest_partial_path(
+    automaton_map: &InMemoryAutomatonMap,
+    layers: &[CellSet],
+    finishing_point: Position,
+) -> Option<PartialPath> {
+    let first_generation = automaton_map.first_generation();
+    let mut closest_state: ClosestState<(usize, Position)> = ClosestState::default();
+
+    for (index, layer) in layers.iter().enumerate() {
+        for position in layer.positions() {
+            closest_state.offer(
+                manhattan_distance(finishing_point, position),
+                first_generation + index as u32,
+                (index, position),
+            );
+        }
+    }
+
+    closest_state.get().map(|(index, position)| {
+        PartialPath::new(
+            walk_back(automaton_map, &layers[..=*index], *position),
+            first_generation,
+            finishing_point,
+        )
+    })
+}
+
+/// Rebuilds a path that ends at `position` in the last layer, picking for each
+/// generation a neighbour that was reachable in the one before it. `layers`
+/// starts at the first generation of `automaton_map`.
+pub fn walk_back(
+    automaton_map: &InMemoryAutomatonMap,
+    layers: &[CellSet],
+    position: Position,
+) -> Vec<Position> {
+    let mut path = vec![position];
+    let mut position = position;
+
+    for index in (1..layers.len()).rev() {
+        let generation = automaton_map.first_generation() + index as u32 - 1;
+        let matrix = automaton_map.get_automaton_matrix_generation(generation);
+
+        position = match adjacent_in(matrix, &layers[index - 1], position) {
+            Some(previous) => previous,
+            None => panic!("Generation {} is not connected to the next one", generation),
+        };
+
+        path.push(position);
+    }
+
+    path.reverse();
+
+    path
+}
@@ -0,0 +1,148 @@
+use crate::automaton::{Automaton, Position};
+use crate::path_finder::astar::automaton_map::InMemoryAutomatonMap;
+use crate::path_finder::bfs::{closest_partial_path, walk_back};
+use crate::path_finder::context::SearchContext;
+use crate::path_finder::outcome::SearchOutcome;
+use crate::path_finder::reachability::{adjacent_in, expand, CellSet};
+use crate::path_finder::shared::manhattan_distance;
+
+/// Bidirectional search over generations.
+///
+/// Since every generation is precomputed, the search can also run backwards
+/// from the finish. For each candidate arrival generation, starting from the
+/// smallest one the Manhattan distance allows, the cells that can still reach
+/// the finish in time are swept backwards until the middle generation, where
+/// they are intersected with the cells reachable from the start. The first
+/// arrival generation with a non empty intersection is the optimum.
+pub fn path_finder(automaton: Automaton, context: &mut SearchContext) -> SearchOutcome {
+    let starting_point = automaton.starting_point;
+    let finishing_point = automaton.finishing_point;
+
+    let height = automaton.matrix.len();
+    let width = automaton.matrix[0].len();
+
+    let automaton_map = context.automaton_map(automaton, 1);
+    let first_generation = automaton_map.first_generation();
+
+    let mut first_layer = CellSet::new(height, width);
+    first_layer.insert(starting_point);
+
+    // forward_layers[index] holds generation first_generation + index
+    let mut forward_memory = first_layer.memory_usage();
+    let mut forward_layers = vec![first_layer];
+
+    let earliest_arrival = first_generation + manhattan_distance(starting_point, finishing_point);
+    let mut expanded_states: u64 = 0;
+
+    for arrival in earliest_arrival..=context.max_generation(automaton_map.generations()) {
+        let memory_usage = automaton_map.memory_usage() + forward_memory;
+
+        let frontier_size = forward_layers.last().unwrap().count();
+
+        if let Some(stop) = context.check(expanded_states, arrival, frontier_size, memory_usage) {
+            let partial = closest_partial_path(&automaton_map, &forward_layers, finishing_point);
+
+            return stop.outcome(partial);
+        }
+
+        let middle = first_generation - 1 + (arrival - first_generation + 1).div_ceil(2);
+        let middle_index = (middle - first_generation) as usize;
+
+        while forward_layers.len() <= middle_index {
+            let generation = first_generation + forward_layers.len() as u32;
+            let matrix = automaton_map.get_automaton_matrix_generation(generation);
+            let frontier = forward_layers.last().unwrap();
+            let layer = expand(matrix, frontier);
+            expanded_states += frontier.count() as u64;
+
+            if layer.is_empty() {
+                return SearchOutcome::NoPath;
+            }
+
+            forward_memory += layer.memory_usage();
+            forward_layers.push(layer);
+        }
+
+        let mut last_layer = CellSet::new(height, width);
+        last_layer.insert(finishing_point);
+
+        // The backward layers of an arrival hold the cells that reach the
+        // finish at exactly that generation, so those of the previous arrival
+        // cannot be reused: each sweep starts over from the finish. Sweeps
+        // stop at the middle generation, and the first arrival with a meeting
+        // point ends the search, so this costs at most the square of the
+        // number of generations searched, in cells of a single board each.
+        //
+        // backward_layers[index] holds generation arrival - index
+        let mut backward_layers = vec![last_layer];
+
+        for generation in (middle..arrival).rev() {
+            let matrix = automaton_map.get_automaton_matrix_generation(generation);
+            let frontier = backward_layers.last().unwrap();
+            let layer = expand(matrix, frontier);
+            expanded_states += frontier.count() as u64;
+
+            if layer.is_empty() {
+                break;
+            }
+
+            backward_layers.push(layer);
+        }
+
+        if backward_layers.len() as u32 != arrival - middle + 1 {
+            continue;
+        }
+
+        let meeting = forward_layers[middle_index].intersection(backward_layers.last().unwrap());
+
+        if let Some(meeting_point) = meeting.positions().first() {
+            let mut path = walk_back(
+                &automaton_map,
+                &forward_layers[..=middle_index],
+                *meeting_point,
+            );
+
+            path.extend(walk_forward(
+                &automaton_map,
+                &backward_layers,
+                arrival,
+                *meeting_point,
+            ));
+
+            return SearchOutcome::Found(path);
+        }
+    }
+
+    let partial = closest_partial_path(&automaton_map, &forward_layers, finishing_point);
+
+    context.horizon_outcome(automaton_map.generations(), partial)
+}
+
+/// Rebuilds the second half of a path, from the meeting point at the first
+/// generation of `backward_layers` (in reverse order) until the finish.
+fn walk_forward(
+    automaton_map: &InMemoryAutomatonMap,
+    backward_layers: &[CellSet],
+    arrival: u32,
+    position: Position,
+) -> Vec<Position> {
+    let mut path = vec![];
+    let mut position = position;
+
+    for index in (0..backward_layers.len() - 1).rev() {
+        let generation = arrival - index as u32;
+        let matrix = automaton_map.get_automaton_matrix_generation(generation);
+
+        position = match adjacent_in(matrix, &backward_layers[index], position) {
+            Some(next) => next,
+            None => panic!(
+                "Generation {} is not connected to the previous one",
+                generation
+            ),
+        };
+
+        path.push(position);
+    }
+
+    path
+}
@@ -0,0 +1,160 @@
+use std::error::Error;
+use std::time::Instant;
+
+use crate::automaton::{Automaton, Position};
+use crate::path_finder::astar::automaton_map::InMemoryAutomatonMap;
+use crate::path_finder::observer::{PruningStats, SearchObserver, SearchStats};
+use crate::path_finder::outcome::{
+    Budget, Interrupt, PartialPath, SearchLimits, SearchOutcome, Stop,
+};
+use crate::path_finder::verification::{verify_path, VerificationReport};
+
+/// Everything a solver reports to or checks with while it runs: the budget,
+/// the observer and the stats handed to it.
+pub struct SearchContext<'a> {
+    budget: Budget,
+    observer: &'a mut dyn SearchObserver,
+    stats: SearchStats,
+    started: Instant,
+    /// States expanded by the previous passes.
+    expanded_before_pass: u64,
+}
+
+impl<'a> SearchContext<'a> {
+    pub fn new(
+        limits: SearchLimits,
+        interrupt: &Interrupt,
+        observer: &'a mut dyn SearchObserver,
+    ) -> Self {
+        SearchContext {
+            budget: Budget::new(limits, interrupt),
+            observer,
+            stats: SearchStats::default(),
+            started: Instant::now(),
+            expanded_before_pass: 0,
+        }
+    }
+
+    /// Records the progress of the current pass, then checks it against the
+    /// budget. `expanded_states` only counts the states of the current pass.
+    pub fn check(
+        &mut self,
+        expanded_states: u64,
+        generation: u32,
+        open_list_size: usize,
+        memory_bytes: usize,
+    ) -> Option<Stop> {
+        let stats = &mut self.stats;
+
+        stats.states_expanded = self.expanded_before_pass + expanded_states;
+        stats.generation = generation;
+        stats.open_list_size = open_list_size;
+        stats.peak_open_list_size = stats.peak_open_list_size.max(open_list_size);
+        stats.memory_bytes = memory_bytes;
+        stats.peak_memory_bytes = stats.peak_memory_bytes.max(memory_bytes);
+
+        self.observer.on_expand(&self.stats);
+
+        self.budget.check(expanded_states, memory_bytes)
+    }
+
+    /// Starts another search over the same problem, such as the next weight
+    /// of the anytime solver. The expanded states limit applies to each pass.
+    pub fn new_pass(&mut self) {
+        self.expanded_before_pass = self.stats.states_expanded;
+    }
+
+    pub fn max_generation(&self, precomputed_generations: u32) -> u32 {
+        self.budget.max_generation(precomputed_generations)
+    }
+
+    pub fn horizon_outcome(
+        &self,
+        precomputed_generations: u32,
+        partial: Option<PartialPath>,
+    ) -> SearchOutcome {
+        self.budget
+            .horizon_outcome(precomputed_generations, partial)
+    }
+
+    pub fn limits(&self) -> &SearchLimits {
+        self.budget.limits()
+    }
+
+    /// Precomputes the generations of `automaton` on `threads` threads,
+    /// reporting each one to the observer.
+    pub fn automaton_map(&mut self, automaton: Automaton, threads: usize) -> InMemoryAutomatonMap {
+        let started = Instant::now();
+
+        let automaton_map =
+            InMemoryAutomatonMap::with_progress(automaton, threads, &mut |generation| {
+                self.observer.on_generation_computed(generation)
+            });
+
+        self.stats.generations_time += started.elapsed();
+
+        automaton_map
+    }
+
+    pub fn pruning(&mut self, pruning: PruningStats) {
+        self.stats.pruning = Some(pruning);
+    }
+
+    pub fn improvement(&mut self, path: &[Position]) {
+        self.observer.on_improvement(path);
+    }
+
+    /// `weight` is in tenths, as the A* solvers take it.
+    pub fn heuristic_weight(&mut self, weight: u32) {
+        self.observer.on_heuristic_weight(weight as f32 / 10.0);
+    }
+
+    pub fn resume(&mut self, iteration: u64) {
+        self.observer.on_resume(iteration);
+    }
+
+    pub fn checkpoint_error(&mut self, error: &dyn Error) {
+        self.observer.on_checkpoint_error(error);
+    }
+
+    /// Verifies a found path, timing the verification. A path that does not
+    /// verify is reported as no path, along with what is wrong with it.
+    pub fn verify(
+        &mut self,
+        automaton: &Automaton,
+        outcome: SearchOutcome,
+    ) -> (SearchOutcome, Option<VerificationReport>) {
+        let started = Instant::now();
+
+        let verified = match outcome {
+            SearchOutcome::Found(path) => {
+                let report = verify_path(automaton, &path);
+
+                if report.is_valid() {
+                    (SearchOutcome::Found(path), Some(report))
+                } else {
+                    (SearchOutcome::NoPath, Some(report))
+                }
+            }
+            outcome => (outcome, None),
+        };
+
+        self.stats.verification_time += started.elapsed();
+
+        verified
+    }
+
+    /// Hands the outcome and the final stats to the observer.
+    pub fn finish(mut self, outcome: &SearchOutcome) -> SearchStats {
+        let stats = &mut self.stats;
+
+        stats.search_time = self
+            .started
+            .elapsed()
+            .saturating_sub(stats.generations_time + stats.verification_time);
+
+        self.observer.on_finish(outcome, &self.stats);
+
+        self.stats
+    }
+}
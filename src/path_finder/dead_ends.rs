@@ -0,0 +1,91 @@
+use crate::automaton::{CellState, Position};
+use crate::path_finder::astar::automaton_map::InMemoryAutomatonMap;
+use crate::path_finder::bfs::layers_memory_usage;
+use crate::path_finder::observer::PruningStats;
+use crate::path_finder::reachability::{expand, CellSet};
+
+/// Every (position, generation) state that can still reach the finish by
+/// a given last generation.
+///
+/// Built by sweeping backwards from the finish over the precomputed
+/// generations: a free cell is live if it is the finish, or if one of its
+/// neighbours is live in the next generation. Any other state is a dead end,
+/// and searching past it is wasted work.
+pub struct LiveStates {
+    first_generation: u32,
+    // layers[index] holds generation first_generation + index
+    layers: Vec<CellSet>,
+    stats: PruningStats,
+    memory_bytes: usize,
+}
+
+impl LiveStates {
+    pub fn new(
+        automaton_map: &InMemoryAutomatonMap,
+        finishing_point: Position,
+        last_generation: u32,
+    ) -> Self {
+        let first_generation = automaton_map.first_generation();
+
+        let matrix = automaton_map.get_automaton_matrix_generation(first_generation);
+        let height = matrix.len();
+        let width = matrix[0].len();
+
+        let mut layers = vec![];
+        let mut stats = PruningStats::default();
+
+        for generation in (first_generation..=last_generation).rev() {
+            let matrix = automaton_map.get_automaton_matrix_generation(generation);
+
+            let mut layer = match layers.last() {
+                Some(next_layer) => expand(matrix, next_layer),
+                None => CellSet::new(height, width),
+            };
+
+            let (i, j) = finishing_point;
+
+            if matrix[i][j] != CellState::Alive {
+                layer.insert(finishing_point);
+            }
+
+            stats.free_states += matrix
+                .iter()
+                .flatten()
+                .filter(|cell| **cell != CellState::Alive)
+                .count() as u64;
+            stats.live_states += layer.count() as u64;
+
+            layers.push(layer);
+        }
+
+        layers.reverse();
+
+        LiveStates {
+            first_generation,
+            memory_bytes: layers_memory_usage(&layers),
+            layers,
+            stats,
+        }
+    }
+
+    /// Whether the finish can still be reached from `position` at `generation`.
+    /// States after the last generation never are.
+    pub fn contains(&self, generation: u32, position: Position) -> bool {
+        match generation.checked_sub(self.first_generation) {
+            Some(index) => self
+                .layers
+                .get(index as usize)
+                .is_some_and(|layer| layer.contains(position)),
+            None => false,
+        }
+    }
+
+    pub fn stats(&self) -> PruningStats {
+        self.stats
+    }
+
+    /// Approximate number of bytes held by the live states.
+    pub fn memory_usage(&self) -> usize {
+        self.memory_bytes
+    }
+}
@@ -0,0 +1,249 @@
+use std::error::Error;
+use std::io::Write;
+use std::time::Duration;
+
+use serde_json::{json, Value};
+
+use crate::automaton::Position;
+use crate::config::{GENERATION_REPORTING_STEP, REPORTING_ITERATION_STEP};
+use crate::path_finder::outcome::{Limit, SearchOutcome};
+
+/// Receives the progress of a search. Every method does nothing by default.
+pub trait SearchObserver {
+    /// Called each time the solver checks its limits: once per expanded state
+    /// for A* and the depth first search, once per generation for the others.
+    fn on_expand(&mut self, _stats: &SearchStats) {}
+
+    fn on_generation_computed(&mut self, _generation: u32) {}
+
+    /// A verified path shorter than every previous one.
+    fn on_improvement(&mut self, _path: &[Position]) {}
+
+    /// The anytime solver starts another pass, with the heuristic multiplied
+    /// by `weight`.
+    fn on_heuristic_weight(&mut self, _weight: f32) {}
+
+    /// A checkpointed search picks up after `iteration` expanded states.
+    fn on_resume(&mut self, _iteration: u64) {}
+
+    /// A checkpoint could not be written. The search goes on without it.
+    fn on_checkpoint_error(&mut self, _error: &dyn Error) {}
+
+    fn on_finish(&mut self, _outcome: &SearchOutcome, _stats: &SearchStats) {}
+}
+
+/// Observer that ignores everything.
+pub struct SilentObserver;
+
+impl SearchObserver for SilentObserver {}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct SearchStats {
+    /// Across every pass, for the solvers that search more than once.
+    pub states_expanded: u64,
+    /// Generation of the last expanded state or frontier.
+    pub generation: u32,
+    pub open_list_size: usize,
+    pub peak_open_list_size: usize,
+    /// Approximate, see `SearchLimits::memory_bytes`.
+    pub memory_bytes: usize,
+    pub peak_memory_bytes: usize,
+    /// Precomputing the generations of the automaton.
+    pub generations_time: Duration,
+    pub search_time: Duration,
+    pub verification_time: Duration,
+    /// Only for the solvers that prune dead ends first.
+    pub pruning: Option<PruningStats>,
+}
+
+/// How much of the state space the dead end pre-pass eliminated, counting the
+/// states on free cells from the first generation to the last precomputed one.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct PruningStats {
+    pub free_states: u64,
+    /// States from which the finish can still be reached.
+    pub live_states: u64,
+}
+
+impl PruningStats {
+    pub fn pruned_states(&self) -> u64 {
+        self.free_states - self.live_states
+    }
+
+    pub fn pruned_fraction(&self) -> f64 {
+        if self.free_states == 0 {
+            return 0.0;
+        }
+
+        self.pruned_states() as f64 / self.free_states as f64
+    }
+}
+
+/// Prints progress every `REPORTING_ITERATION_STEP` expanded states and
+/// every `GENERATION_REPORTING_STEP` computed generations, then the final stats.
+#[derive(Default)]
+pub struct LogObserver {
+    next_report: u64,
+}
+
+impl SearchObserver for LogObserver {
+    fn on_expand(&mut self, stats: &SearchStats) {
+        if stats.states_expanded < self.next_report {
+            return;
+        }
+
+        println!(
+            "Expanded {} states, generation {}, open list {}, memory {} bytes",
+            stats.states_expanded, stats.generation, stats.open_list_size, stats.memory_bytes
+        );
+
+        self.next_report = next_report(stats.states_expanded);
+    }
+
+    fn on_generation_computed(&mut self, generation: u32) {
+        if generation.is_multiple_of(GENERATION_REPORTING_STEP) {
+            println!("Generated {} automaton maps", generation);
+        }
+    }
+
+    fn on_improvement(&mut self, path: &[Position]) {
+        println!("Improved path length: {}", path.len());
+    }
+
+    fn on_heuristic_weight(&mut self, weight: f32) {
+        println!("Searching with heuristic weight {}", weight);
+    }
+
+    fn on_resume(&mut self, iteration: u64) {
+        println!("Resuming search from iteration {}", iteration);
+    }
+
+    fn on_checkpoint_error(&mut self, error: &dyn Error) {
+        println!("Unable to write checkpoint: {}", error);
+    }
+
+    fn on_finish(&mut self, outcome: &SearchOutcome, stats: &SearchStats) {
+        println!("Search finished: {}", outcome_name(outcome));
+        println!("States expanded: {}", stats.states_expanded);
+        println!("Peak open list size: {}", stats.peak_open_list_size);
+        println!("Peak memory estimate: {} bytes", stats.peak_memory_bytes);
+        println!("Time computing generations: {:?}", stats.generations_time);
+        println!("Time searching: {:?}", stats.search_time);
+        println!("Time verifying: {:?}", stats.verification_time);
+
+        if let Some(pruning) = stats.pruning {
+            println!(
+                "Pruned dead ends: {} of {} states ({:.1}%)",
+                pruning.pruned_states(),
+                pruning.free_states,
+                pruning.pruned_fraction() * 100.0
+            );
+        }
+    }
+}
+
+/// Writes every event as a JSON object on its own line. Expansions are
+/// written every `REPORTING_ITERATION_STEP` expanded states.
+pub struct JsonLinesObserver<W: Write> {
+    writer: W,
+    next_report: u64,
+}
+
+impl<W: Write> JsonLinesObserver<W> {
+    pub fn new(writer: W) -> Self {
+        JsonLinesObserver {
+            writer,
+            next_report: 0,
+        }
+    }
+
+    fn write(&mut self, event: Value) {
+        if let Err(error) = writeln!(self.writer, "{}", event) {
+            println!("Unable to write search event: {}", error);
+        }
+    }
+}
+
+impl<W: Write> SearchObserver for JsonLinesObserver<W> {
+    fn on_expand(&mut self, stats: &SearchStats) {
+        if stats.states_expanded < self.next_report {
+            return;
+        }
+
+        self.next_report = next_report(stats.states_expanded);
+
+        self.write(json!({
+            "event": "expand",
+            "states_expanded": stats.states_expanded,
+            "generation": stats.generation,
+            "open_list_size": stats.open_list_size,
+            "memory_bytes": stats.memory_bytes,
+        }));
+    }
+
+    fn on_generation_computed(&mut self, generation: u32) {
+        self.write(json!({ "event": "generation_computed", "generation": generation }));
+    }
+
+    fn on_improvement(&mut self, path: &[Position]) {
+        self.write(json!({ "event": "improvement", "length": path.len(), "path": path }));
+    }
+
+    fn on_heuristic_weight(&mut self, weight: f32) {
+        self.write(json!({ "event": "heuristic_weight", "weight": weight }));
+    }
+
+    fn on_resume(&mut self, iteration: u64) {
+        self.write(json!({ "event": "resume", "iteration": iteration }));
+    }
+
+    fn on_checkpoint_error(&mut self, error: &dyn Error) {
+        self.write(json!({ "event": "checkpoint_error", "error": error.to_string() }));
+    }
+
+    fn on_finish(&mut self, outcome: &SearchOutcome, stats: &SearchStats) {
+        let path_length = match outcome {
+            SearchOutcome::Found(path) => Some(path.len()),
+            _ => None,
+        };
+
+        let pruned_states = stats.pruning.map(|pruning| pruning.pruned_states());
+        let free_states = stats.pruning.map(|pruning| pruning.free_states);
+
+        self.write(json!({
+            "event": "finish",
+            "outcome": outcome_name(outcome),
+            "path_length": path_length,
+            "states_expanded": stats.states_expanded,
+            "peak_open_list_size": stats.peak_open_list_size,
+            "peak_memory_bytes": stats.peak_memory_bytes,
+            "generations_seconds": stats.generations_time.as_secs_f64(),
+            "search_seconds": stats.search_time.as_secs_f64(),
+            "verification_seconds": stats.verification_time.as_secs_f64(),
+            "pruned_states": pruned_states,
+            "free_states": free_states,
+        }));
+
+        if let Err(error) = self.writer.flush() {
+            println!("Unable to write search event: {}", error);
+        }
+    }
+}
+
+fn next_report(states_expanded: u64) -> u64 {
+    (states_expanded / REPORTING_ITERATION_STEP + 1) * REPORTING_ITERATION_STEP
+}
+
+fn outcome_name(outcome: &SearchOutcome) -> &'static str {
+    match outcome {
+        SearchOutcome::Found(_) => "found",
+        SearchOutcome::NoPath => "no_path",
+        SearchOutcome::LimitExceeded { which, .. } => match which {
+            Limit::WallTime => "time_limit_exceeded",
+            Limit::ExpandedStates => "expanded_states_limit_exceeded",
+            Limit::MaxGeneration => "generation_limit_exceeded",
+            Limit::Memory => "memory_limit_exceeded",
+        },
+        SearchOutcome::Interrupted(_) => "interrupted",
+    }
+}
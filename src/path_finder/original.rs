@@ -1,110 +1,250 @@
+use std::collections::HashSet;
+
 use crate::automaton::{Automaton, Position};
+use crate::config::{ORIGINAL_CALL_LIMIT, ORIGINAL_GENERATION_LIMIT};
+use crate::path_finder::context::SearchContext;
+use crate::path_finder::outcome::{ClosestState, Limit, PartialPath, SearchOutcome, Stop};
 use crate::path_finder::shared::{get_possible_moves, manhattan_distance};
-use rand::Rng;
+use crate::path_finder::verify_result;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
 
 use super::astar::automaton_map::InMemoryAutomatonMap;
 
-pub fn path_finder(automaton: Automaton) -> Option<Vec<Position>> {
-    let starting_point = automaton.starting_point;
-    let finishing_point = automaton.finishing_point;
-
-    let automaton_map = InMemoryAutomatonMap::new(automaton.clone());
-
-    let result = helper(
-        automaton.generation,
-        starting_point,
-        finishing_point,
-        vec![starting_point],
-        &automaton_map,
-    );
-
-    if result.1 == false {
-        return None;
-    }
+pub const DEFAULT_SEED: u64 = 0;
 
-    return Some(result.0);
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct DfsOptions {
+    /// Seed of the first attempt. Restart `n` uses `seed + n`.
+    pub seed: u64,
+    /// Number of attempts. Only the shortest path found is kept.
+    pub restarts: usize,
 }
-static mut FUNCTION_CALL_COUNT: u32 = 0;
-
-fn helper(
-    generation: u32,
-    starting_point: Position,
-    ending_point: Position,
-    path: Vec<Position>,
-    automaton_map: &InMemoryAutomatonMap,
-) -> (Vec<Position>, bool) {
-    unsafe {
-        FUNCTION_CALL_COUNT += 1;
-
-        if FUNCTION_CALL_COUNT >= 10000 {
-            return (path, false);
+
+impl Default for DfsOptions {
+    fn default() -> Self {
+        DfsOptions {
+            seed: DEFAULT_SEED,
+            restarts: 1,
         }
     }
+}
 
-    if starting_point == ending_point {
-        return (path, true);
-    }
+/// Greedy depth first search, trying the moves closest to the finish first
+/// and breaking ties with a seeded random number generator.
+///
+/// Every attempt after the first one only looks for paths shorter than the
+/// best one so far. Unless the budget says otherwise, an attempt gives up after
+/// `ORIGINAL_CALL_LIMIT` expansions or `ORIGINAL_GENERATION_LIMIT` generations
+/// after the start.
+pub fn path_finder(
+    automaton: Automaton,
+    options: DfsOptions,
+    context: &mut SearchContext,
+) -> SearchOutcome {
+    let starting_generation = automaton.generation;
+    let starting_point = automaton.starting_point;
+    let finishing_point = automaton.finishing_point;
 
-    if generation == 1000 {
-        println!("Generation limit reached");
-        return (path, false);
-    }
+    let automaton_map = context.automaton_map(automaton.clone(), 1);
 
-    let next_generation = generation + 1;
+    let limits = context.limits();
+    let max_generation = limits
+        .max_generation
+        .unwrap_or(starting_generation - 1 + ORIGINAL_GENERATION_LIMIT)
+        .min(automaton_map.generations());
+
+    let mut dfs = Dfs {
+        automaton_map: &automaton_map,
+        finishing_point,
+        call_limit: limits.expanded_states.unwrap_or(ORIGINAL_CALL_LIMIT),
+        closest_state: ClosestState::default(),
+    };
+
+    let shortest_possible = 1 + manhattan_distance(starting_point, finishing_point) as usize;
+
+    let mut best: Option<Vec<Position>> = None;
+    let mut exceeded_limit: Option<Limit> = None;
+
+    for restart in 0..options.restarts.max(1) {
+        let seed = options.seed.wrapping_add(restart as u64);
+        let mut rng = StdRng::seed_from_u64(seed);
+
+        // A path of length n arrives at generation starting_generation + n - 1
+        let last_generation = match &best {
+            Some(path) if path.len() == shortest_possible => break,
+            Some(path) => starting_generation + path.len() as u32 - 2,
+            None => max_generation,
+        };
+
+        context.new_pass();
+
+        match dfs.attempt(
+            starting_generation,
+            starting_point,
+            last_generation,
+            &mut rng,
+            context,
+        ) {
+            // Improvements must be verified, drop the attempt otherwise
+            Attempt::Found(path) if !verify_result(&automaton, &path) => {}
+            Attempt::Found(path) => {
+                context.improvement(&path);
+                best = Some(path);
+            }
+            Attempt::Exhausted => {}
+            Attempt::Stopped(Stop::LimitExceeded(
+                which @ (Limit::ExpandedStates | Limit::MaxGeneration),
+            )) => {
+                exceeded_limit = Some(which);
+            }
+            Attempt::Stopped(stop) => {
+                let partial = match best {
+                    Some(path) => Some(path),
+                    None => dfs.closest_state.get().cloned(),
+                };
+
+                return stop.outcome(
+                    partial
+                        .map(|path| PartialPath::new(path, starting_generation, finishing_point)),
+                );
+            }
+        }
+    }
 
-    let next_gen_automaton_matrix = automaton_map.get_automaton_matrix_generation(next_generation);
+    match (best, exceeded_limit) {
+        (Some(path), _) => SearchOutcome::Found(path),
+        (None, Some(which)) => {
+            let partial = dfs
+                .closest_state
+                .get()
+                .map(|path| PartialPath::new(path.clone(), starting_generation, finishing_point));
 
-    let mut possible_moves = get_possible_moves(&next_gen_automaton_matrix, starting_point);
-    if possible_moves.len() == 0 {
-        return (path, false);
+            Stop::LimitExceeded(which).outcome(partial)
+        }
+        (None, None) => SearchOutcome::NoPath,
     }
+}
 
-    possible_moves.sort_by(|vec1, vec2| {
-        let a_distance = manhattan_distance(ending_point, *vec1);
-        let b_distance = manhattan_distance(ending_point, *vec2);
-        a_distance.cmp(&b_distance)
-    });
+enum Attempt {
+    Found(Vec<Position>),
+    /// Every reachable state is a dead end, none of them in the last generation.
+    Exhausted,
+    Stopped(Stop),
+}
 
-    // There will be at most 4 possible moves
-    // Of those, only 2 can have the same distance to the finish
-    // If this is the case, we randomly choose which one to go first
-    // That way, if there are multiple solutions,
-    // We can get slightly different results each time
+struct Dfs<'a> {
+    automaton_map: &'a InMemoryAutomatonMap,
+    finishing_point: Position,
+    call_limit: u64,
+    /// Shared by every attempt.
+    closest_state: ClosestState<Vec<Position>>,
+}
 
-    if possible_moves.len() > 1 {
-        let first_option_distance = manhattan_distance(ending_point, possible_moves[0]);
-        let second_option_distance = manhattan_distance(ending_point, possible_moves[1]);
+impl Dfs<'_> {
+    fn attempt(
+        &mut self,
+        starting_generation: u32,
+        starting_point: Position,
+        last_generation: u32,
+        rng: &mut StdRng,
+        context: &mut SearchContext,
+    ) -> Attempt {
+        let finishing_point = self.finishing_point;
+
+        let mut path = vec![starting_point];
+        // pending[index] holds the moves left to try from path[index], best one last
+        let mut pending: Vec<Vec<Position>> = vec![];
+        let mut visited: HashSet<(u32, Position)> = HashSet::new();
+        let mut calls: u64 = 0;
+        // Number of moves in pending
+        let mut open_list_size: usize = 0;
+        let mut has_reached_last_generation = false;
+
+        loop {
+            let position = *path.last().unwrap();
+            let generation = starting_generation + path.len() as u32 - 1;
+
+            let memory_usage = self.automaton_map.memory_usage()
+                + visited.len() * size_of::<(u32, Position)>()
+                + pending.len() * size_of::<Vec<Position>>()
+                + path.len() * size_of::<Position>();
+
+            if let Some(stop) = context.check(calls, generation, open_list_size, memory_usage) {
+                return Attempt::Stopped(stop);
+            }
 
-        if first_option_distance == second_option_distance {
-            let mut rng = rand::thread_rng();
-            let random_bool = rng.gen_bool(0.5);
+            self.closest_state.offer_with(
+                manhattan_distance(finishing_point, position),
+                generation,
+                || path.clone(),
+            );
 
-            if random_bool {
-                let first_option = possible_moves[0].clone();
-                let second_option = possible_moves[1].clone();
+            calls += 1;
 
-                possible_moves[0] = second_option;
-                possible_moves[1] = first_option;
+            if position == finishing_point {
+                return Attempt::Found(path);
             }
-        }
-    }
 
-    for point in possible_moves {
-        let mut path_clone = path.clone();
-        path_clone.push(point);
+            if calls >= self.call_limit {
+                return Attempt::Stopped(Stop::LimitExceeded(Limit::ExpandedStates));
+            }
 
-        let result = helper(
-            next_generation,
-            point,
-            ending_point,
-            path_clone,
-            &automaton_map,
-        );
+            let next_generation = generation + 1;
+
+            let mut moves: Vec<Position> = if generation == last_generation {
+                has_reached_last_generation = true;
+                vec![]
+            } else {
+                let matrix = self
+                    .automaton_map
+                    .get_automaton_matrix_generation(next_generation);
+
+                get_possible_moves(matrix, position)
+                    .into_iter()
+                    .filter(|next| visited.insert((next_generation, *next)))
+                    .collect()
+            };
+
+            moves.sort_by_key(|next| manhattan_distance(finishing_point, *next));
+
+            // There will be at most 4 possible moves
+            // Of those, only 2 can have the same distance to the finish
+            // If this is the case, the seed decides which one goes first
+            if moves.len() > 1
+                && manhattan_distance(finishing_point, moves[0])
+                    == manhattan_distance(finishing_point, moves[1])
+                && rng.gen_bool(0.5)
+            {
+                moves.swap(0, 1);
+            }
 
-        if result.1 {
-            return result;
+            moves.reverse();
+            open_list_size += moves.len();
+            pending.push(moves);
+
+            // Backtrack until a position with a move left to try
+            loop {
+                let moves = match pending.last_mut() {
+                    Some(moves) => moves,
+                    None if has_reached_last_generation => {
+                        return Attempt::Stopped(Stop::LimitExceeded(Limit::MaxGeneration))
+                    }
+                    None => return Attempt::Exhausted,
+                };
+
+                match moves.pop() {
+                    Some(next) => {
+                        open_list_size -= 1;
+                        path.push(next);
+                        break;
+                    }
+                    None => {
+                        pending.pop();
+                        path.pop();
+                    }
+                }
+            }
         }
     }
-
-    return (path, false);
 }
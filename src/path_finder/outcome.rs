@@ -0,0 +1,224 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use crate::automaton::Position;
+use crate::path_finder::shared::manhattan_distance;
+
+/// How a search ended.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum SearchOutcome {
+    Found(Vec<Position>),
+    NoPath,
+    /// The search ran out of one of its `SearchLimits` before it could finish.
+    /// Holds the best partial path it had reached.
+    LimitExceeded {
+        which: Limit,
+        partial: Option<PartialPath>,
+    },
+    /// The search was stopped through its `Interrupt` before it could finish.
+    /// Holds the best partial path it had reached.
+    Interrupted(Option<PartialPath>),
+}
+
+impl SearchOutcome {
+    pub fn path(self) -> Option<Vec<Position>> {
+        match self {
+            SearchOutcome::Found(path) => Some(path),
+            _ => None,
+        }
+    }
+}
+
+/// Resources a single search may use. Every limit is optional.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct SearchLimits {
+    pub wall_time: Option<Duration>,
+    pub expanded_states: Option<u64>,
+    pub max_generation: Option<u32>,
+    /// Approximate, counting the precomputed generations and the search's own
+    /// bookkeeping.
+    pub memory_bytes: Option<usize>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Limit {
+    WallTime,
+    ExpandedStates,
+    MaxGeneration,
+    Memory,
+}
+
+/// Why a search has to stop before finishing.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Stop {
+    Interrupted,
+    LimitExceeded(Limit),
+}
+
+impl Stop {
+    pub fn outcome(self, partial: Option<PartialPath>) -> SearchOutcome {
+        match self {
+            Stop::Interrupted => SearchOutcome::Interrupted(partial),
+            Stop::LimitExceeded(which) => SearchOutcome::LimitExceeded { which, partial },
+        }
+    }
+}
+
+/// Limits and interrupt of a running search, checked by the solvers once per
+/// expansion or generation.
+#[derive(Clone, Debug)]
+pub struct Budget {
+    limits: SearchLimits,
+    interrupt: Interrupt,
+    started: Instant,
+}
+
+impl Budget {
+    pub fn new(limits: SearchLimits, interrupt: &Interrupt) -> Self {
+        Budget {
+            limits,
+            interrupt: interrupt.clone(),
+            started: Instant::now(),
+        }
+    }
+
+    pub fn check(&self, expanded_states: u64, memory_bytes: usize) -> Option<Stop> {
+        if self.interrupt.is_triggered() {
+            return Some(Stop::Interrupted);
+        }
+
+        if let Some(limit) = self.limits.expanded_states {
+            if expanded_states >= limit {
+                return Some(Stop::LimitExceeded(Limit::ExpandedStates));
+            }
+        }
+
+        if let Some(limit) = self.limits.memory_bytes {
+            if memory_bytes >= limit {
+                return Some(Stop::LimitExceeded(Limit::Memory));
+            }
+        }
+
+        if let Some(limit) = self.limits.wall_time {
+            if self.started.elapsed() >= limit {
+                return Some(Stop::LimitExceeded(Limit::WallTime));
+            }
+        }
+
+        None
+    }
+
+    /// Last generation the search may reach: the `max_generation` limit, or
+    /// the last precomputed generation if that comes first.
+    pub fn max_generation(&self, precomputed_generations: u32) -> u32 {
+        match self.limits.max_generation {
+            Some(limit) => limit.min(precomputed_generations),
+            None => precomputed_generations,
+        }
+    }
+
+    /// Outcome of a search that went through every generation up to
+    /// `max_generation` without reaching the finish. A path may still arrive
+    /// after a `max_generation` limit, but none is looked for after the last
+    /// precomputed generation.
+    pub fn horizon_outcome(
+        &self,
+        precomputed_generations: u32,
+        partial: Option<PartialPath>,
+    ) -> SearchOutcome {
+        match self.limits.max_generation {
+            Some(limit) if limit < precomputed_generations => {
+                Stop::LimitExceeded(Limit::MaxGeneration).outcome(partial)
+            }
+            _ => SearchOutcome::NoPath,
+        }
+    }
+
+    pub fn limits(&self) -> &SearchLimits {
+        &self.limits
+    }
+}
+
+/// Shared flag that asks a running search to stop, e.g. from a Ctrl-C handler.
+#[derive(Clone, Debug, Default)]
+pub struct Interrupt(Arc<AtomicBool>);
+
+impl Interrupt {
+    pub fn new() -> Self {
+        Interrupt::default()
+    }
+
+    pub fn trigger(&self) {
+        self.0.store(true, Ordering::SeqCst);
+    }
+
+    pub fn is_triggered(&self) -> bool {
+        self.0.load(Ordering::Relaxed)
+    }
+}
+
+/// Path from the start to the state that got the closest to the finish.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PartialPath {
+    pub path: Vec<Position>,
+    pub generation: u32,
+    pub position: Position,
+    pub distance_to_finish: u32,
+}
+
+impl PartialPath {
+    pub fn new(path: Vec<Position>, starting_generation: u32, finishing_point: Position) -> Self {
+        let position = *path.last().unwrap();
+
+        PartialPath {
+            generation: starting_generation + path.len() as u32 - 1,
+            position,
+            distance_to_finish: manhattan_distance(finishing_point, position),
+            path,
+        }
+    }
+}
+
+/// Keeps the state closest to the finish seen so far, the deepest one among
+/// those at the same distance. Generic over however a solver identifies states.
+pub struct ClosestState<T> {
+    best: Option<(u32, u32, T)>,
+}
+
+impl<T> Default for ClosestState<T> {
+    fn default() -> Self {
+        ClosestState { best: None }
+    }
+}
+
+impl<T> ClosestState<T> {
+    pub fn offer(&mut self, distance_to_finish: u32, generation: u32, state: T) {
+        self.offer_with(distance_to_finish, generation, || state);
+    }
+
+    /// Same as `offer`, only building the state when it is kept, for states
+    /// that are costly to copy.
+    pub fn offer_with(
+        &mut self,
+        distance_to_finish: u32,
+        generation: u32,
+        state: impl FnOnce() -> T,
+    ) {
+        let is_better = match &self.best {
+            Some((best_distance, best_generation, _)) => {
+                distance_to_finish < *best_distance
+                    || (distance_to_finish == *best_distance && generation > *best_generation)
+            }
+            None => true,
+        };
+
+        if is_better {
+            self.best = Some((distance_to_finish, generation, state()));
+        }
+    }
+
+    pub fn get(&self) -> Option<&T> {
+        self.best.as_ref().map(|(_, _, state)| state)
+    }
+}
@@ -0,0 +1,156 @@
+use crate::automaton::{CellState, Position};
+use crate::path_finder::shared::get_possible_moves;
+use std::thread;
+
+const BITS_PER_WORD: usize = 64;
+
+/// Set of cells of a board, one bit per cell.
+///
+/// Used to hold every position the particle can occupy at a given generation,
+/// which is all the time-expanded searches need to know about a generation.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct CellSet {
+    height: usize,
+    width: usize,
+    words: Vec<u64>,
+}
+
+impl CellSet {
+    pub fn new(height: usize, width: usize) -> Self {
+        CellSet {
+            height,
+            width,
+            words: vec![0; (height * width).div_ceil(BITS_PER_WORD)],
+        }
+    }
+
+    pub fn insert(&mut self, (i, j): Position) {
+        let index = i * self.width + j;
+        self.words[index / BITS_PER_WORD] |= 1 << (index % BITS_PER_WORD);
+    }
+
+    pub fn contains(&self, (i, j): Position) -> bool {
+        let index = i * self.width + j;
+        self.words[index / BITS_PER_WORD] & (1 << (index % BITS_PER_WORD)) != 0
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.words.iter().all(|word| *word == 0)
+    }
+
+    pub fn count(&self) -> usize {
+        self.words
+            .iter()
+            .map(|word| word.count_ones() as usize)
+            .sum()
+    }
+
+    /// Approximate number of bytes held by the set.
+    pub fn memory_usage(&self) -> usize {
+        self.words.len() * size_of::<u64>()
+    }
+
+    pub fn union_with(&mut self, other: &CellSet) {
+        for (word, other_word) in self.words.iter_mut().zip(other.words.iter()) {
+            *word |= other_word;
+        }
+    }
+
+    pub fn intersection(&self, other: &CellSet) -> CellSet {
+        CellSet {
+            height: self.height,
+            width: self.width,
+            words: self
+                .words
+                .iter()
+                .zip(other.words.iter())
+                .map(|(a, b)| a & b)
+                .collect(),
+        }
+    }
+
+    pub fn positions(&self) -> Vec<Position> {
+        let mut positions = vec![];
+
+        for (word_index, word) in self.words.iter().enumerate() {
+            let mut word = *word;
+
+            while word != 0 {
+                let index = word_index * BITS_PER_WORD + word.trailing_zeros() as usize;
+                positions.push((index / self.width, index % self.width));
+                word &= word - 1;
+            }
+        }
+
+        positions
+    }
+}
+
+/// Every cell that is free in `matrix` and adjacent to a cell of `cells`.
+///
+/// Going forward in time, `matrix` is the next generation and the result holds
+/// where the particle can be after one more move. Going backward, `matrix` is
+/// the previous generation and the result holds where the particle could have
+/// come from.
+pub fn expand(matrix: &Vec<Vec<CellState>>, cells: &CellSet) -> CellSet {
+    let mut expanded = CellSet::new(cells.height, cells.width);
+
+    for position in cells.positions() {
+        for neighbour in get_possible_moves(matrix, position) {
+            expanded.insert(neighbour);
+        }
+    }
+
+    expanded
+}
+
+/// Same as `expand`, with the cells split between `threads` threads. Each
+/// thread fills its own set and the sets are merged, so the result does not
+/// depend on the number of threads.
+pub fn expand_parallel(matrix: &Vec<Vec<CellState>>, cells: &CellSet, threads: usize) -> CellSet {
+    let positions = cells.positions();
+
+    if threads <= 1 || positions.len() < threads {
+        return expand(matrix, cells);
+    }
+
+    let chunk_size = positions.len().div_ceil(threads);
+
+    thread::scope(|scope| {
+        let handles: Vec<_> = positions
+            .chunks(chunk_size)
+            .map(|chunk| {
+                scope.spawn(move || {
+                    let mut expanded = CellSet::new(cells.height, cells.width);
+
+                    for position in chunk {
+                        for neighbour in get_possible_moves(matrix, *position) {
+                            expanded.insert(neighbour);
+                        }
+                    }
+
+                    expanded
+                })
+            })
+            .collect();
+
+        let mut expanded = CellSet::new(cells.height, cells.width);
+
+        for handle in handles {
+            expanded.union_with(&handle.join().unwrap());
+        }
+
+        expanded
+    })
+}
+
+/// First neighbour of `position` that belongs to `cells`.
+pub fn adjacent_in(
+    matrix: &Vec<Vec<CellState>>,
+    cells: &CellSet,
+    position: Position,
+) -> Option<Position> {
+    get_possible_moves(matrix, position)
+        .into_iter()
+        .find(|neighbour| cells.contains(*neighbour))
+}
@@ -42,3 +42,56 @@ pub fn manhattan_distance(from: Position, to: Position) -> u32 {
 
     return distance as u32;
 }
+
+/// One of the four moves a particle can make between generations.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Direction {
+    Up,
+    Down,
+    Left,
+    Right,
+}
+
+impl Direction {
+    pub fn between(from: Position, to: Position) -> Direction {
+        if to.0 < from.0 {
+            Direction::Up
+        } else if to.0 > from.0 {
+            Direction::Down
+        } else if to.1 < from.1 {
+            Direction::Left
+        } else {
+            Direction::Right
+        }
+    }
+
+    /// Position the particle was at before moving in this direction to `position`.
+    pub fn origin(&self, position: Position) -> Position {
+        let (i, j) = position;
+
+        match self {
+            Direction::Up => (i + 1, j),
+            Direction::Down => (i - 1, j),
+            Direction::Left => (i, j + 1),
+            Direction::Right => (i, j - 1),
+        }
+    }
+
+    pub fn code(&self) -> u64 {
+        match self {
+            Direction::Up => 0,
+            Direction::Down => 1,
+            Direction::Left => 2,
+            Direction::Right => 3,
+        }
+    }
+
+    pub fn from_code(code: u64) -> Direction {
+        match code & 0b11 {
+            0 => Direction::Up,
+            1 => Direction::Down,
+            2 => Direction::Left,
+            _ => Direction::Right,
+        }
+    }
+}
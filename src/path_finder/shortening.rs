@@ -0,0 +1,157 @@
+use crate::automaton::{Automaton, CellState, Position};
+use crate::path_finder::reachability::{adjacent_in, expand, CellSet};
+use crate::path_finder::verify_result;
+
+/// Default number of generations a detour may replace.
+pub const DEFAULT_WINDOW: usize = 40;
+
+/// Steps `from` to `to` of a path, replaced by a detour of `steps` steps.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Detour {
+    pub from: usize,
+    pub to: usize,
+    pub steps: usize,
+}
+
+/// Shortens a verified path by replacing pieces of it with shorter detours.
+///
+/// From each step of the path, the cells reachable in the following
+/// generations are swept for up to `window` generations. Whenever a later
+/// step of the path is reached earlier than the path itself gets there, and
+/// the rest of the path is still valid once moved that many generations
+/// earlier, the piece in between is replaced by the detour. This repeats until
+/// no detour is found.
+///
+/// The returned path is verified again, and is never longer than `path`.
+pub fn shorten_path(automaton: &Automaton, path: &[Position], window: usize) -> Vec<Position> {
+    shorten_path_with_progress(automaton, path, window, &mut |_| {})
+}
+
+/// Same as `shorten_path`, calling `on_detour` after each replacement.
+pub fn shorten_path_with_progress(
+    automaton: &Automaton,
+    path: &[Position],
+    window: usize,
+    on_detour: &mut dyn FnMut(Detour),
+) -> Vec<Position> {
+    let mut path = path.to_vec();
+
+    if path.len() < 3 {
+        return path;
+    }
+
+    let height = automaton.matrix.len();
+    let width = automaton.matrix[0].len();
+
+    // matrices[index] holds the generation of path[index]
+    let mut matrices = vec![automaton.matrix.clone()];
+    let mut current = automaton.clone();
+
+    while matrices.len() < path.len() {
+        current = current.iterate();
+        matrices.push(current.matrix.clone());
+    }
+
+    let mut from = 0;
+
+    while from + 2 < path.len() {
+        match find_detour(&matrices, &path, from, window, height, width) {
+            Some((detour, to)) => {
+                on_detour(Detour {
+                    from,
+                    to,
+                    steps: detour.len() - 1,
+                });
+
+                let mut shortened = path[..from].to_vec();
+                shortened.extend(detour);
+                shortened.extend_from_slice(&path[to + 1..]);
+                path = shortened;
+            }
+            None => from += 1,
+        }
+    }
+
+    if !verify_result(automaton, &path) {
+        panic!("Shortened path is not valid");
+    }
+
+    path
+}
+
+/// Shortest detour from `path[from]` to a later step `path[to]` that arrives
+/// earlier than the path does and keeps the rest of the path valid. Returns the
+/// detour, from `path[from]` to `path[to]` included, and `to`.
+fn find_detour(
+    matrices: &[Vec<Vec<CellState>>],
+    path: &[Position],
+    from: usize,
+    window: usize,
+    height: usize,
+    width: usize,
+) -> Option<(Vec<Position>, usize)> {
+    let last = (from + window).min(path.len() - 1);
+
+    let mut first_layer = CellSet::new(height, width);
+    first_layer.insert(path[from]);
+
+    // layers[index] holds the cells reachable at the generation of path[from + index]
+    let mut layers = vec![first_layer];
+
+    for arrival in from + 1..last {
+        let layer = expand(&matrices[arrival], layers.last().unwrap());
+
+        if layer.is_empty() {
+            return None;
+        }
+
+        layers.push(layer);
+
+        // The furthest step saves the most generations
+        for to in (arrival + 1..=last).rev() {
+            if layers.last().unwrap().contains(path[to])
+                && is_valid_earlier(matrices, &path[to + 1..], arrival + 1)
+            {
+                let detour = walk_back(matrices, &layers, from, path[to]);
+
+                return Some((detour, to));
+            }
+        }
+    }
+
+    None
+}
+
+/// Whether `steps` is still valid when its first step happens at the
+/// generation of path index `index`.
+fn is_valid_earlier(matrices: &[Vec<Vec<CellState>>], steps: &[Position], index: usize) -> bool {
+    steps
+        .iter()
+        .enumerate()
+        .all(|(offset, (i, j))| matrices[index + offset][*i][*j] != CellState::Alive)
+}
+
+fn walk_back(
+    matrices: &[Vec<Vec<CellState>>],
+    layers: &[CellSet],
+    from: usize,
+    position: Position,
+) -> Vec<Position> {
+    let mut detour = vec![position];
+    let mut position = position;
+
+    for index in (1..layers.len()).rev() {
+        let matrix = &matrices[from + index - 1];
+
+        position = match adjacent_in(matrix, &layers[index - 1], position) {
+            Some(previous) => previous,
+            None => panic!("Step {} is not connected to the next one", from + index),
+        };
+
+        detour.push(position);
+    }
+
+    detour.reverse();
+
+    detour
+}
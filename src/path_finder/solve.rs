@@ -0,0 +1,72 @@
+use std::error::Error;
+use std::time::Duration;
+
+use crate::automaton::{Automaton, Position};
+use crate::path_finder::observer::{SearchObserver, SearchStats, SilentObserver};
+use crate::path_finder::verification::VerificationReport;
+use crate::path_finder::{Interrupt, SearchLimits, SearchOutcome, Solver, Start};
+
+#[derive(Clone, Debug, Default)]
+pub struct SolveOptions {
+    pub solver: Solver,
+    pub limits: SearchLimits,
+    /// Starts somewhere other than the starting point of the automaton at its
+    /// current generation. The generation cannot be before the current one.
+    pub start: Option<Start>,
+    /// Triggering it, e.g. from another thread, stops the search.
+    pub interrupt: Interrupt,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SolveResult {
+    pub outcome: SearchOutcome,
+    /// Report on the path returned by the solver, if any. A path that failed
+    /// verification turns the outcome into `NoPath` but keeps its report.
+    pub verification: Option<VerificationReport>,
+    /// Final stats, including the time spent in each phase.
+    pub stats: SearchStats,
+}
+
+impl SolveResult {
+    pub fn path(&self) -> Option<&[Position]> {
+        match &self.outcome {
+            SearchOutcome::Found(path) => Some(path),
+            _ => None,
+        }
+    }
+
+    pub fn length(&self) -> Option<usize> {
+        self.path().map(|path| path.len())
+    }
+
+    pub fn elapsed(&self) -> Duration {
+        self.stats.generations_time + self.stats.search_time + self.stats.verification_time
+    }
+}
+
+/// Finds a path through `automaton` without printing anything.
+///
+/// Fails without searching if the custom start is invalid, see `Start::apply`.
+pub fn solve(automaton: &Automaton, options: &SolveOptions) -> Result<SolveResult, Box<dyn Error>> {
+    solve_with_observer(automaton, options, &mut SilentObserver)
+}
+
+/// Same as `solve`, reporting the progress of the search to `observer`.
+pub fn solve_with_observer(
+    automaton: &Automaton,
+    options: &SolveOptions,
+    observer: &mut dyn SearchObserver,
+) -> Result<SolveResult, Box<dyn Error>> {
+    let automaton = match options.start {
+        Some(start) => start.apply(automaton)?,
+        None => automaton.clone(),
+    };
+
+    Ok(super::search(
+        &automaton,
+        options.solver,
+        options.limits,
+        &options.interrupt,
+        observer,
+    ))
+}
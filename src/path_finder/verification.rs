@@ -0,0 +1,76 @@
+use crate::automaton::{Automaton, CellState, Position};
+use crate::path_finder::shared::manhattan_distance;
+
+/// Everything wrong with a path, step by step.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct VerificationReport {
+    pub length: usize,
+    pub starts_at_start: bool,
+    pub ends_at_finish: bool,
+    /// Steps that are not a move to one of the four adjacent cells.
+    pub invalid_moves: Vec<usize>,
+    /// Steps outside of the board.
+    pub out_of_bounds: Vec<usize>,
+    pub collisions: Vec<Collision>,
+}
+
+/// Step of a path that lands on an alive cell.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Collision {
+    pub step: usize,
+    pub generation: u32,
+    pub position: Position,
+}
+
+impl VerificationReport {
+    pub fn is_valid(&self) -> bool {
+        self.length > 0
+            && self.starts_at_start
+            && self.ends_at_finish
+            && self.invalid_moves.is_empty()
+            && self.out_of_bounds.is_empty()
+            && self.collisions.is_empty()
+    }
+}
+
+/// Checks `path` against `automaton`, step `index` happening at generation
+/// `automaton.generation + index`.
+pub fn verify_path(automaton: &Automaton, path: &[Position]) -> VerificationReport {
+    let height = automaton.matrix.len();
+    let width = automaton.matrix[0].len();
+
+    let mut report = VerificationReport {
+        length: path.len(),
+        starts_at_start: path.first() == Some(&automaton.starting_point),
+        ends_at_finish: path.last() == Some(&automaton.finishing_point),
+        ..VerificationReport::default()
+    };
+
+    for (step, pair) in path.windows(2).enumerate() {
+        if manhattan_distance(pair[0], pair[1]) != 1 {
+            report.invalid_moves.push(step + 1);
+        }
+    }
+
+    let mut automaton = automaton.clone();
+
+    for (step, position) in path.iter().enumerate() {
+        let (i, j) = *position;
+
+        if i >= height || j >= width {
+            report.out_of_bounds.push(step);
+        } else if automaton.matrix[i][j] == CellState::Alive {
+            report.collisions.push(Collision {
+                step,
+                generation: automaton.generation,
+                position: *position,
+            });
+        }
+
+        if step + 1 < path.len() {
+            automaton = automaton.iterate();
+        }
+    }
+
+    report
+}
@@ -0,0 +1,204 @@
+use crate::automaton::{Automaton, CellState, Position};
+
+pub mod animation;
+pub mod image;
+pub mod svg;
+pub mod terminal;
+
+pub type Rgb = [u8; 3];
+
+/// How a path over the board is drawn.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct RenderOptions {
+    /// Side of a cell, in pixels.
+    pub cell_size: usize,
+    pub frames_per_second: u32,
+    pub dead_colour: Rgb,
+    pub alive_colour: Rgb,
+    /// Cells the particle went through.
+    pub trail_colour: Rgb,
+    pub particle_colour: Rgb,
+    pub start_colour: Rgb,
+    pub finish_colour: Rgb,
+}
+
+impl Default for RenderOptions {
+    fn default() -> Self {
+        RenderOptions {
+            cell_size: 8,
+            frames_per_second: 10,
+            dead_colour: [255, 255, 255],
+            alive_colour: [40, 40, 40],
+            trail_colour: [120, 170, 255],
+            particle_colour: [230, 40, 40],
+            start_colour: [40, 180, 80],
+            finish_colour: [250, 180, 20],
+        }
+    }
+}
+
+impl RenderOptions {
+    pub fn colour(&self, tile: Tile) -> Rgb {
+        match tile {
+            Tile::Dead => self.dead_colour,
+            Tile::Alive => self.alive_colour,
+            Tile::Trail => self.trail_colour,
+            Tile::Particle => self.particle_colour,
+            Tile::Start => self.start_colour,
+            Tile::Finish => self.finish_colour,
+        }
+    }
+}
+
+/// Parses a colour written as `#rrggbb` or `rrggbb`.
+pub fn parse_colour(hex: &str) -> Result<Rgb, String> {
+    let digits = hex.strip_prefix('#').unwrap_or(hex);
+
+    if digits.len() != 6 || !digits.chars().all(|digit| digit.is_ascii_hexdigit()) {
+        return Err(format!("Invalid colour: {}", hex));
+    }
+
+    let channel = |index: usize| u8::from_str_radix(&digits[index..index + 2], 16).unwrap();
+
+    Ok([channel(0), channel(2), channel(4)])
+}
+
+/// `from` moved `part` out of `parts` of the way to `to`.
+pub(crate) fn mix(from: Rgb, to: Rgb, part: usize, parts: usize) -> Rgb {
+    let channel = |index: usize| {
+        let from = from[index] as usize * (parts - part);
+        let to = to[index] as usize * part;
+
+        ((from + to) / parts) as u8
+    };
+
+    [channel(0), channel(1), channel(2)]
+}
+
+/// What a cell of a frame shows, from the most to the least important.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Tile {
+    Particle,
+    Start,
+    Finish,
+    Alive,
+    Trail,
+    Dead,
+}
+
+impl Tile {
+    /// Every tile, in the order used for palettes.
+    pub const ALL: [Tile; 6] = [
+        Tile::Dead,
+        Tile::Alive,
+        Tile::Trail,
+        Tile::Particle,
+        Tile::Start,
+        Tile::Finish,
+    ];
+
+    pub fn index(self) -> u8 {
+        Tile::ALL.iter().position(|tile| *tile == self).unwrap() as u8
+    }
+}
+
+/// The board at one step of a path.
+pub struct Frame {
+    pub step: usize,
+    pub generation: u32,
+    /// Where the particle is.
+    pub position: Position,
+    pub height: usize,
+    pub width: usize,
+    /// Row by row.
+    pub tiles: Vec<Tile>,
+}
+
+impl Frame {
+    pub fn tile(&self, (i, j): Position) -> Tile {
+        self.tiles[i * self.width + j]
+    }
+}
+
+/// Frames of a path over the board, one per step, computed by stepping the
+/// automaton alongside the path so only one generation is held at a time.
+pub struct Frames<'a> {
+    automaton: Automaton,
+    path: &'a [Position],
+    step: usize,
+    visited: Vec<bool>,
+}
+
+impl<'a> Frames<'a> {
+    /// Step `index` of `path` happens at generation `automaton.generation + index`.
+    pub fn new(automaton: &Automaton, path: &'a [Position]) -> Self {
+        let height = automaton.matrix.len();
+        let width = automaton.matrix[0].len();
+
+        Frames {
+            automaton: automaton.clone(),
+            path,
+            step: 0,
+            visited: vec![false; height * width],
+        }
+    }
+}
+
+impl Iterator for Frames<'_> {
+    type Item = Frame;
+
+    fn next(&mut self) -> Option<Frame> {
+        let position = *self.path.get(self.step)?;
+
+        if self.step > 0 {
+            self.automaton = self.automaton.iterate();
+        }
+
+        let matrix = &self.automaton.matrix;
+        let height = matrix.len();
+        let width = matrix[0].len();
+
+        let (i, j) = position;
+
+        if i < height && j < width {
+            self.visited[i * width + j] = true;
+        }
+
+        let mut tiles = Vec::with_capacity(height * width);
+
+        for (row_index, row) in matrix.iter().enumerate() {
+            for (column_index, cell) in row.iter().enumerate() {
+                let cell_position = (row_index, column_index);
+
+                let tile = if cell_position == position {
+                    Tile::Particle
+                } else if cell_position == self.automaton.starting_point {
+                    Tile::Start
+                } else if cell_position == self.automaton.finishing_point {
+                    Tile::Finish
+                } else if *cell == CellState::Alive {
+                    Tile::Alive
+                } else if self.visited[row_index * width + column_index] {
+                    Tile::Trail
+                } else {
+                    Tile::Dead
+                };
+
+                tiles.push(tile);
+            }
+        }
+
+        let frame = Frame {
+            step: self.step,
+            generation: self.automaton.generation,
+            position,
+            height,
+            width,
+            tiles,
+        };
+
+        self.step += 1;
+
+        Some(frame)
+    }
+}
@@ -0,0 +1,72 @@
+use std::error::Error;
+use std::io::Write;
+
+use gif::{Encoder, Frame as GifFrame, Repeat};
+
+use crate::automaton::{Automaton, Position};
+use crate::render::{Frame, Frames, RenderOptions, Tile};
+
+/// Writes `path` over `automaton` as an animated GIF, one frame per step,
+/// looping forever.
+pub fn write_gif<W: Write>(
+    automaton: &Automaton,
+    path: &[Position],
+    options: &RenderOptions,
+    writer: W,
+) -> Result<(), Box<dyn Error>> {
+    if path.is_empty() {
+        return Err("Cannot render an empty path".into());
+    }
+
+    if options.cell_size == 0 || options.frames_per_second == 0 {
+        return Err("Cell size and frame rate must be positive".into());
+    }
+
+    let width = u16::try_from(automaton.matrix[0].len() * options.cell_size)
+        .map_err(|_| "Board is too wide for a GIF, use a smaller cell size")?;
+    let height = u16::try_from(automaton.matrix.len() * options.cell_size)
+        .map_err(|_| "Board is too tall for a GIF, use a smaller cell size")?;
+
+    let palette: Vec<u8> = Tile::ALL
+        .iter()
+        .flat_map(|tile| options.colour(*tile))
+        .collect();
+
+    // GIF delays are in hundredths of a second
+    let delay = (100 / options.frames_per_second).max(1) as u16;
+
+    let mut encoder = Encoder::new(writer, width, height, &palette)?;
+    encoder.set_repeat(Repeat::Infinite)?;
+
+    for frame in Frames::new(automaton, path) {
+        let pixels = indexed_pixels(&frame, options.cell_size);
+
+        let mut gif_frame = GifFrame::from_indexed_pixels(width, height, pixels, None);
+        gif_frame.delay = delay;
+
+        encoder.write_frame(&gif_frame)?;
+    }
+
+    // Writes the trailer, which dropping the encoder would do without errors
+    encoder.into_inner()?.flush()?;
+
+    Ok(())
+}
+
+/// Palette index of every pixel of `frame`, row by row.
+fn indexed_pixels(frame: &Frame, cell_size: usize) -> Vec<u8> {
+    let mut pixels = Vec::with_capacity(frame.tiles.len() * cell_size * cell_size);
+
+    for row in frame.tiles.chunks(frame.width) {
+        let pixel_row: Vec<u8> = row
+            .iter()
+            .flat_map(|tile| std::iter::repeat_n(tile.index(), cell_size))
+            .collect();
+
+        for _ in 0..cell_size {
+            pixels.extend_from_slice(&pixel_row);
+        }
+    }
+
+    pixels
+}
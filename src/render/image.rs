@@ -0,0 +1,260 @@
+use std::error::Error;
+use std::io::Write;
+
+use png::{BitDepth, ColorType, Encoder};
+
+use crate::automaton::{Automaton, Position};
+use crate::render::{mix, Frame, Frames, RenderOptions, Rgb, Tile};
+
+/// Pixels between the thumbnails of a contact sheet.
+pub const SHEET_GAP: usize = 4;
+
+/// Which part of the board is drawn, and how small.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct View {
+    /// Side of the square of cells drawn as a single cell, shaded by how many
+    /// of them are alive. 1 draws every cell.
+    pub downsample: usize,
+    /// Only draws the cells up to this many rows and columns away from the
+    /// particle, shifted at the edges of the board so every frame has the
+    /// same size.
+    pub crop_radius: Option<usize>,
+}
+
+impl Default for View {
+    fn default() -> Self {
+        View {
+            downsample: 1,
+            crop_radius: None,
+        }
+    }
+}
+
+/// RGB image, row by row.
+pub struct Image {
+    pub width: usize,
+    pub height: usize,
+    pub pixels: Vec<u8>,
+}
+
+impl Image {
+    pub fn pixel(&self, x: usize, y: usize) -> Rgb {
+        let index = (y * self.width + x) * 3;
+
+        [
+            self.pixels[index],
+            self.pixels[index + 1],
+            self.pixels[index + 2],
+        ]
+    }
+}
+
+/// Rows and columns of the board drawn for a frame.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+struct Region {
+    top: usize,
+    left: usize,
+    height: usize,
+    width: usize,
+}
+
+impl View {
+    fn region(&self, frame: &Frame) -> Region {
+        let Some(radius) = self.crop_radius else {
+            return Region {
+                top: 0,
+                left: 0,
+                height: frame.height,
+                width: frame.width,
+            };
+        };
+
+        let (i, j) = frame.position;
+        let height = (2 * radius + 1).min(frame.height);
+        let width = (2 * radius + 1).min(frame.width);
+
+        Region {
+            top: i.saturating_sub(radius).min(frame.height - height),
+            left: j.saturating_sub(radius).min(frame.width - width),
+            height,
+            width,
+        }
+    }
+
+    /// Size in pixels of the image of a `height` by `width` board.
+    pub fn image_size(&self, height: usize, width: usize, cell_size: usize) -> (usize, usize) {
+        let (height, width) = match self.crop_radius {
+            Some(radius) => ((2 * radius + 1).min(height), (2 * radius + 1).min(width)),
+            None => (height, width),
+        };
+
+        (
+            width.div_ceil(self.downsample) * cell_size,
+            height.div_ceil(self.downsample) * cell_size,
+        )
+    }
+}
+
+/// Draws `frame` as seen through `view`.
+///
+/// A downsampled cell shows the particle, start or finish if it holds one,
+/// otherwise a mix of the alive colour and the dead (or trail, if the
+/// particle went through it) colour, weighted by how many cells are alive.
+pub fn draw(frame: &Frame, view: &View, options: &RenderOptions) -> Image {
+    let region = view.region(frame);
+    let (width, height) = view.image_size(frame.height, frame.width, options.cell_size);
+
+    let mut pixels = Vec::with_capacity(width * height * 3);
+
+    for block_top in (region.top..region.top + region.height).step_by(view.downsample) {
+        let block_bottom = (block_top + view.downsample).min(region.top + region.height);
+
+        let mut row = Vec::with_capacity(width * 3);
+
+        for block_left in (region.left..region.left + region.width).step_by(view.downsample) {
+            let block_right = (block_left + view.downsample).min(region.left + region.width);
+
+            let tiles = (block_top..block_bottom)
+                .flat_map(|i| (block_left..block_right).map(move |j| (i, j)))
+                .map(|position| frame.tile(position));
+
+            let colour = block_colour(tiles, options);
+
+            for _ in 0..options.cell_size {
+                row.extend_from_slice(&colour);
+            }
+        }
+
+        for _ in 0..options.cell_size {
+            pixels.extend_from_slice(&row);
+        }
+    }
+
+    Image {
+        width,
+        height,
+        pixels,
+    }
+}
+
+fn block_colour(tiles: impl Iterator<Item = Tile>, options: &RenderOptions) -> Rgb {
+    let mut most_important = Tile::Dead;
+    let mut alive = 0;
+    let mut count = 0;
+
+    for tile in tiles {
+        most_important = most_important.min(tile);
+
+        if tile == Tile::Alive {
+            alive += 1;
+        }
+
+        count += 1;
+    }
+
+    match most_important {
+        Tile::Particle | Tile::Start | Tile::Finish => options.colour(most_important),
+        Tile::Trail => mix(options.trail_colour, options.alive_colour, alive, count),
+        Tile::Alive | Tile::Dead => mix(options.dead_colour, options.alive_colour, alive, count),
+    }
+}
+
+pub fn write_png<W: Write>(image: &Image, writer: W) -> Result<(), Box<dyn Error>> {
+    let mut encoder = Encoder::new(writer, image.width as u32, image.height as u32);
+    encoder.set_color(ColorType::Rgb);
+    encoder.set_depth(BitDepth::Eight);
+
+    let mut writer = encoder.write_header()?;
+    writer.write_image_data(&image.pixels)?;
+    writer.finish()?;
+
+    Ok(())
+}
+
+/// Writes the board at every `every`th step of `path`, and at its last step,
+/// as thumbnails laid out `columns` to a row in a single PNG.
+///
+/// The automaton is stepped alongside the path and the sheet is written one
+/// row of thumbnails at a time, so neither every generation nor the whole
+/// sheet is held in memory.
+pub fn write_contact_sheet<W: Write>(
+    automaton: &Automaton,
+    path: &[Position],
+    every: usize,
+    columns: usize,
+    view: &View,
+    options: &RenderOptions,
+    writer: W,
+) -> Result<(), Box<dyn Error>> {
+    if path.is_empty() {
+        return Err("Cannot render an empty path".into());
+    }
+
+    if every == 0 || columns == 0 || view.downsample == 0 || options.cell_size == 0 {
+        return Err("Step, columns, downsampling and cell size must be positive".into());
+    }
+
+    let last_step = path.len() - 1;
+    let thumbnails = last_step / every + 1 + usize::from(!last_step.is_multiple_of(every));
+    let columns = columns.min(thumbnails);
+    let rows = thumbnails.div_ceil(columns);
+
+    let (thumbnail_width, thumbnail_height) = view.image_size(
+        automaton.matrix.len(),
+        automaton.matrix[0].len(),
+        options.cell_size,
+    );
+
+    let width = columns * thumbnail_width + (columns + 1) * SHEET_GAP;
+    let height = rows * thumbnail_height + (rows + 1) * SHEET_GAP;
+
+    let mut encoder = Encoder::new(writer, width as u32, height as u32);
+    encoder.set_color(ColorType::Rgb);
+    encoder.set_depth(BitDepth::Eight);
+
+    let mut writer = encoder.write_header()?;
+    let mut stream = writer.stream_writer()?;
+
+    let gap_row = options.alive_colour.repeat(width);
+
+    let mut selected = Frames::new(automaton, path)
+        .filter(|frame| frame.step.is_multiple_of(every) || frame.step == last_step)
+        .map(|frame| draw(&frame, view, options));
+
+    for _ in 0..rows {
+        for _ in 0..SHEET_GAP {
+            stream.write_all(&gap_row)?;
+        }
+
+        let images: Vec<Image> = selected.by_ref().take(columns).collect();
+
+        for y in 0..thumbnail_height {
+            let mut row = Vec::with_capacity(width * 3);
+
+            for column in 0..columns {
+                row.extend_from_slice(&gap_row[..SHEET_GAP * 3]);
+
+                match images.get(column) {
+                    Some(image) => {
+                        let start = y * thumbnail_width * 3;
+                        row.extend_from_slice(&image.pixels[start..start + thumbnail_width * 3]);
+                    }
+                    None => row.extend_from_slice(&gap_row[..thumbnail_width * 3]),
+                }
+            }
+
+            row.extend_from_slice(&gap_row[..SHEET_GAP * 3]);
+
+            stream.write_all(&row)?;
+        }
+    }
+
+    for _ in 0..SHEET_GAP {
+        stream.write_all(&gap_row)?;
+    }
+
+    stream.finish()?;
+    writer.finish()?;
+
+    Ok(())
+}
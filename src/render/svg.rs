@@ -0,0 +1,194 @@
+use std::error::Error;
+use std::io::Write;
+
+use crate::automaton::{Automaton, Position};
+use crate::path_finder::verification::verify_path;
+use crate::render::{mix, RenderOptions, Rgb};
+
+/// Writes the board at `generation` as an SVG, with the whole of `path` drawn
+/// over it one segment per step, shading from the start colour to the finish
+/// colour as the generations go by.
+///
+/// The start and the finish are marked, as well as the particle if it is on
+/// the board at `generation`, and every step that collides with an alive cell
+/// according to `verify_path`. Each segment and marker has a title telling
+/// its step and generation.
+pub fn write_svg<W: Write>(
+    automaton: &Automaton,
+    path: &[Position],
+    generation: u32,
+    options: &RenderOptions,
+    mut writer: W,
+) -> Result<(), Box<dyn Error>> {
+    if generation < automaton.generation {
+        return Err(format!(
+            "Generation {} is before the first one, {}",
+            generation, automaton.generation
+        )
+        .into());
+    }
+
+    let board = automaton.advance_to(generation);
+    let matrix = board.to_integer_matrix();
+    let report = verify_path(automaton, path);
+
+    let cell_size = options.cell_size;
+    let height = matrix.len() * cell_size;
+    let width = matrix[0].len() * cell_size;
+
+    writeln!(
+        writer,
+        r#"<svg xmlns="http://www.w3.org/2000/svg" width="{}" height="{}" viewBox="0 0 {} {}">"#,
+        width, height, width, height
+    )?;
+    writeln!(writer, "<title>Generation {}</title>", generation)?;
+    writeln!(
+        writer,
+        r#"<rect width="{}" height="{}" fill="{}"/>"#,
+        width,
+        height,
+        hex(options.dead_colour)
+    )?;
+
+    // One rectangle per run of alive cells keeps large boards small
+    writeln!(
+        writer,
+        r#"<g class="alive" fill="{}">"#,
+        hex(options.alive_colour)
+    )?;
+
+    for (i, row) in matrix.iter().enumerate() {
+        let mut j = 0;
+
+        while j < row.len() {
+            if row[j] != 1 {
+                j += 1;
+                continue;
+            }
+
+            let run_start = j;
+
+            while j < row.len() && row[j] == 1 {
+                j += 1;
+            }
+
+            writeln!(
+                writer,
+                r#"<rect x="{}" y="{}" width="{}" height="{}"/>"#,
+                run_start * cell_size,
+                i * cell_size,
+                (j - run_start) * cell_size,
+                cell_size
+            )?;
+        }
+    }
+
+    writeln!(writer, "</g>")?;
+
+    let centre = |(i, j): Position| {
+        (
+            (j as f64 + 0.5) * cell_size as f64,
+            (i as f64 + 0.5) * cell_size as f64,
+        )
+    };
+    let stroke_width = (cell_size as f64 / 4.0).max(1.0);
+
+    writeln!(
+        writer,
+        r#"<g class="path" stroke-width="{}" stroke-linecap="round">"#,
+        stroke_width
+    )?;
+
+    for (step, pair) in path.windows(2).enumerate() {
+        let (x1, y1) = centre(pair[0]);
+        let (x2, y2) = centre(pair[1]);
+        let colour = mix(
+            options.start_colour,
+            options.finish_colour,
+            step,
+            path.len() - 1,
+        );
+
+        writeln!(
+            writer,
+            r#"<line class="step" x1="{}" y1="{}" x2="{}" y2="{}" stroke="{}"><title>Step {}, generation {}</title></line>"#,
+            x1,
+            y1,
+            x2,
+            y2,
+            hex(colour),
+            step + 1,
+            automaton.generation + step as u32 + 1
+        )?;
+    }
+
+    writeln!(writer, "</g>")?;
+
+    let radius = cell_size as f64 / 2.0;
+
+    for (class, position, colour) in [
+        ("start", board.starting_point, options.start_colour),
+        ("finish", board.finishing_point, options.finish_colour),
+    ] {
+        let (x, y) = centre(position);
+
+        writeln!(
+            writer,
+            r#"<rect class="{}" x="{}" y="{}" width="{}" height="{}" fill="none" stroke="{}" stroke-width="{}"/>"#,
+            class,
+            x - radius,
+            y - radius,
+            cell_size,
+            cell_size,
+            hex(colour),
+            stroke_width
+        )?;
+    }
+
+    let step = (generation - automaton.generation) as usize;
+
+    if let Some(position) = path.get(step) {
+        let (x, y) = centre(*position);
+
+        writeln!(
+            writer,
+            r#"<circle class="particle" cx="{}" cy="{}" r="{}" fill="{}"><title>Step {}, generation {}</title></circle>"#,
+            x,
+            y,
+            radius,
+            hex(options.particle_colour),
+            step,
+            generation
+        )?;
+    }
+
+    for collision in &report.collisions {
+        let (x, y) = centre(collision.position);
+
+        writeln!(
+            writer,
+            r#"<path class="collision" d="M{} {}L{} {}M{} {}L{} {}" stroke="{}" stroke-width="{}"><title>Collision at step {}, generation {}</title></path>"#,
+            x - radius,
+            y - radius,
+            x + radius,
+            y + radius,
+            x - radius,
+            y + radius,
+            x + radius,
+            y - radius,
+            hex(options.particle_colour),
+            stroke_width,
+            collision.step,
+            collision.generation
+        )?;
+    }
+
+    writeln!(writer, "</svg>")?;
+    writer.flush()?;
+
+    Ok(())
+}
+
+fn hex([red, green, blue]: Rgb) -> String {
+    format!("#{:02x}{:02x}{:02x}", red, green, blue)
+}
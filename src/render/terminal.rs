@@ -0,0 +1,323 @@
+use std::io::{self, Write};
+use std::time::Duration;
+
+use crossterm::cursor::{Hide, MoveTo, Show};
+use crossterm::event::{self, Event, KeyCode, KeyEvent, KeyEventKind};
+use crossterm::style::{Attribute, Color, Print, SetAttribute, SetForegroundColor};
+use crossterm::terminal::{
+    self, disable_raw_mode, enable_raw_mode, Clear, ClearType, EnterAlternateScreen,
+    LeaveAlternateScreen,
+};
+use crossterm::{execute, queue};
+
+use crate::automaton::{Automaton, CellState, Position};
+use crate::path_finder::shared::manhattan_distance;
+
+/// Steps between the generations kept while replaying, so stepping back only
+/// recomputes from the closest one instead of from the start.
+pub const SNAPSHOT_STEP: usize = 16;
+
+/// Lines below the board for the status and the keys.
+const STATUS_LINES: u16 = 3;
+
+const MIN_DELAY: Duration = Duration::from_millis(20);
+const MAX_DELAY: Duration = Duration::from_millis(2000);
+
+/// A path being replayed over the evolving board, one step at a time.
+pub struct Replay<'a> {
+    path: &'a [Position],
+    // snapshots[index] holds the board at step index * SNAPSHOT_STEP
+    snapshots: Vec<Automaton>,
+    step: usize,
+    current: Automaton,
+    /// Board at the next step, to show which cells will be alive.
+    next: Automaton,
+    pub cursor: Position,
+    /// Whether the viewport is centred on the particle rather than the cursor.
+    pub follow: bool,
+    pub playing: bool,
+    pub delay: Duration,
+}
+
+/// What is under the cursor.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct CursorInfo {
+    pub position: Position,
+    pub state: CellState,
+    pub alive_neighbours: i32,
+    pub alive_next: bool,
+}
+
+impl<'a> Replay<'a> {
+    /// Step `index` of `path` happens at generation `automaton.generation + index`.
+    pub fn new(automaton: &Automaton, path: &'a [Position]) -> Self {
+        Replay {
+            path,
+            snapshots: vec![automaton.clone()],
+            step: 0,
+            current: automaton.clone(),
+            next: automaton.iterate(),
+            cursor: path.first().copied().unwrap_or(automaton.starting_point),
+            follow: true,
+            playing: false,
+            delay: Duration::from_millis(200),
+        }
+    }
+
+    pub fn step(&self) -> usize {
+        self.step
+    }
+
+    pub fn last_step(&self) -> usize {
+        self.path.len().saturating_sub(1)
+    }
+
+    pub fn board(&self) -> &Automaton {
+        &self.current
+    }
+
+    pub fn particle(&self) -> Position {
+        self.path[self.step]
+    }
+
+    pub fn distance_to_finish(&self) -> u32 {
+        manhattan_distance(self.particle(), self.current.finishing_point)
+    }
+
+    pub fn forward(&mut self) {
+        if self.step == self.last_step() {
+            return;
+        }
+
+        self.step += 1;
+        self.current = self.next.iterate();
+        std::mem::swap(&mut self.current, &mut self.next);
+
+        if self.snapshots.len() * SNAPSHOT_STEP == self.step {
+            self.snapshots.push(self.current.clone());
+        }
+    }
+
+    pub fn back(&mut self) {
+        if self.step > 0 {
+            self.go_to(self.step - 1);
+        }
+    }
+
+    pub fn go_to(&mut self, step: usize) {
+        let step = step.min(self.last_step());
+
+        if step < self.step {
+            let snapshot = (step / SNAPSHOT_STEP).min(self.snapshots.len() - 1);
+
+            self.step = snapshot * SNAPSHOT_STEP;
+            self.current = self.snapshots[snapshot].clone();
+            self.next = self.current.iterate();
+        }
+
+        while self.step < step {
+            self.forward();
+        }
+    }
+
+    pub fn cursor_info(&self) -> CursorInfo {
+        let (i, j) = self.cursor;
+
+        CursorInfo {
+            position: self.cursor,
+            state: self.current.matrix[i][j],
+            alive_neighbours: self.current.get_number_of_alive_neighbours(self.cursor),
+            alive_next: self.next.matrix[i][j] == CellState::Alive,
+        }
+    }
+
+    pub fn move_cursor(&mut self, rows: isize, columns: isize) {
+        let height = self.current.matrix.len();
+        let width = self.current.matrix[0].len();
+
+        let (i, j) = self.cursor;
+
+        self.cursor = (
+            i.saturating_add_signed(rows).min(height - 1),
+            j.saturating_add_signed(columns).min(width - 1),
+        );
+        self.follow = false;
+    }
+
+    /// Applies a key press. Returns false once the replay should stop.
+    pub fn handle_key(&mut self, key: KeyCode) -> bool {
+        match key {
+            KeyCode::Char('q') | KeyCode::Esc => return false,
+            KeyCode::Right | KeyCode::Char('n') => self.forward(),
+            KeyCode::Left | KeyCode::Char('p') => self.back(),
+            KeyCode::Home => self.go_to(0),
+            KeyCode::End => self.go_to(self.last_step()),
+            KeyCode::Char(' ') => self.playing = !self.playing,
+            KeyCode::Char('h') => self.move_cursor(0, -1),
+            KeyCode::Char('j') => self.move_cursor(1, 0),
+            KeyCode::Char('k') => self.move_cursor(-1, 0),
+            KeyCode::Char('l') => self.move_cursor(0, 1),
+            KeyCode::Char('f') => self.follow = !self.follow,
+            KeyCode::Char('c') => self.cursor = self.particle(),
+            KeyCode::Char('+') => self.delay = (self.delay / 2).max(MIN_DELAY),
+            KeyCode::Char('-') => self.delay = (self.delay * 2).min(MAX_DELAY),
+            _ => (),
+        }
+
+        true
+    }
+
+    /// First row and column shown in a viewport of `rows` by `columns` cells,
+    /// centred on the particle or the cursor.
+    fn viewport(&self, rows: usize, columns: usize) -> Position {
+        let height = self.current.matrix.len();
+        let width = self.current.matrix[0].len();
+
+        let (i, j) = if self.follow {
+            self.particle()
+        } else {
+            self.cursor
+        };
+
+        (
+            i.saturating_sub(rows / 2).min(height.saturating_sub(rows)),
+            j.saturating_sub(columns / 2)
+                .min(width.saturating_sub(columns)),
+        )
+    }
+}
+
+/// Replays `path` over `automaton` in the terminal until `q` is pressed.
+pub fn replay(automaton: &Automaton, path: &[Position]) -> io::Result<()> {
+    if path.is_empty() {
+        return Err(io::Error::other("Cannot replay an empty path"));
+    }
+
+    let mut replay = Replay::new(automaton, path);
+    let mut stdout = io::stdout();
+
+    enable_raw_mode()?;
+    execute!(stdout, EnterAlternateScreen, Hide)?;
+
+    let result = run(&mut replay, &mut stdout);
+
+    execute!(stdout, Show, LeaveAlternateScreen)?;
+    disable_raw_mode()?;
+
+    result
+}
+
+fn run(replay: &mut Replay, stdout: &mut impl Write) -> io::Result<()> {
+    loop {
+        draw(replay, stdout)?;
+
+        let timeout = if replay.playing {
+            replay.delay
+        } else {
+            Duration::from_secs(60)
+        };
+
+        if event::poll(timeout)? {
+            if let Event::Key(KeyEvent {
+                code,
+                kind: KeyEventKind::Press,
+                ..
+            }) = event::read()?
+            {
+                if !replay.handle_key(code) {
+                    return Ok(());
+                }
+            }
+        } else if replay.playing {
+            replay.forward();
+            replay.playing = replay.step() < replay.last_step();
+        }
+    }
+}
+
+fn draw(replay: &Replay, stdout: &mut impl Write) -> io::Result<()> {
+    let (terminal_columns, terminal_rows) = terminal::size()?;
+
+    // Two characters per cell keep the cells roughly square
+    let rows = terminal_rows.saturating_sub(STATUS_LINES) as usize;
+    let columns = terminal_columns as usize / 2;
+
+    let board = replay.board();
+    let height = board.matrix.len();
+    let width = board.matrix[0].len();
+
+    let (top, left) = replay.viewport(rows, columns);
+    let trail = &replay.path[..replay.step];
+
+    queue!(stdout, Clear(ClearType::All))?;
+
+    for i in top..(top + rows).min(height) {
+        queue!(stdout, MoveTo(0, (i - top) as u16))?;
+
+        for j in left..(left + columns).min(width) {
+            let position = (i, j);
+
+            let (glyph, colour) = if position == replay.particle() {
+                ("()", Color::Red)
+            } else if position == board.starting_point {
+                ("S ", Color::Green)
+            } else if position == board.finishing_point {
+                ("F ", Color::Yellow)
+            } else if board.matrix[i][j] == CellState::Alive {
+                if replay.next.matrix[i][j] == CellState::Alive {
+                    ("██", Color::White)
+                } else {
+                    ("▓▓", Color::DarkGrey)
+                }
+            } else if replay.next.matrix[i][j] == CellState::Alive {
+                ("░░", Color::DarkYellow)
+            } else if trail.contains(&position) {
+                ("··", Color::Blue)
+            } else {
+                ("  ", Color::Reset)
+            };
+
+            if position == replay.cursor {
+                queue!(stdout, SetAttribute(Attribute::Reverse))?;
+            }
+
+            queue!(
+                stdout,
+                SetForegroundColor(colour),
+                Print(glyph),
+                SetAttribute(Attribute::Reset)
+            )?;
+        }
+    }
+
+    let cursor = replay.cursor_info();
+    let status_row = rows as u16;
+
+    queue!(
+        stdout,
+        MoveTo(0, status_row),
+        Print(format!(
+            "Step {}/{}  Generation {}  Distance to finish {}  {}{}",
+            replay.step(),
+            replay.last_step(),
+            board.generation,
+            replay.distance_to_finish(),
+            if replay.playing { "playing" } else { "paused" },
+            if replay.follow { ", following" } else { "" },
+        )),
+        MoveTo(0, status_row + 1),
+        Print(format!(
+            "Cursor {:?}: {:?}, {} alive neighbours, {} next generation",
+            cursor.position,
+            cursor.state,
+            cursor.alive_neighbours,
+            if cursor.alive_next { "alive" } else { "dead" },
+        )),
+        MoveTo(0, status_row + 2),
+        SetForegroundColor(Color::DarkGrey),
+        Print("←/→ step  home/end  space play  +/- speed  hjkl cursor  c cursor to particle  f follow  q quit"),
+        SetAttribute(Attribute::Reset),
+    )?;
+
+    stdout.flush()
+}
@@ -0,0 +1,218 @@
+use std::cmp::Reverse;
+use std::error::Error;
+
+use crate::automaton::{Automaton, CellState, Position};
+use crate::path_finder::reachability::{expand, CellSet};
+use crate::path_finder::shared::manhattan_distance;
+use crate::path_finder::solve::{solve, SolveOptions};
+use crate::stats::default_horizon;
+
+/// Flips tried before giving up on a board, unless told otherwise.
+pub const DEFAULT_MAX_FLIPS: usize = 4;
+
+/// A board made solvable by flipping some of its cells.
+#[derive(Clone)]
+pub struct Repair {
+    /// Cells flipped in the first generation, in row-major order.
+    pub flips: Vec<Position>,
+    /// Whether no smaller set of flips can make the board solvable. Always
+    /// known for up to two flips, since every single flip is tried.
+    pub proven_minimum: bool,
+    pub automaton: Automaton,
+    pub path: Vec<Position>,
+    /// Boards stepped through to find the flips.
+    pub evaluated_boards: u64,
+}
+
+/// How far the particle gets on a board.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+struct Progress {
+    /// Moves to the finish, if it is reached.
+    arrival: Option<u32>,
+    /// Smallest distance to the finish of any reachable cell.
+    closest: u32,
+    /// Generations with at least one reachable cell.
+    survived: u32,
+}
+
+impl Progress {
+    /// Smaller is better.
+    fn rank(&self) -> (Reverse<bool>, u32, u32, Reverse<u32>) {
+        (
+            Reverse(self.arrival.is_some()),
+            self.arrival.unwrap_or(0),
+            self.closest,
+            Reverse(self.survived),
+        )
+    }
+}
+
+/// Finds a small set of cells to flip in the current generation of
+/// `automaton` so the particle can reach the finish within the generations
+/// the solvers look at, along with a path through the repaired board.
+///
+/// Every single flip is tried first. If none is enough, the flip that takes
+/// the particle closest to the finish is kept and the search goes on from the
+/// flipped board, up to `max_flips` flips. Flips that turn out not to be
+/// needed are then dropped, so none of the returned ones can be left out.
+///
+/// Each board is judged by stepping the automaton along with the set of
+/// reachable cells. Only the cells close enough to the start to change the
+/// reachable cells before they all die out are tried.
+pub fn repair(automaton: &Automaton, max_flips: usize) -> Result<Repair, Box<dyn Error>> {
+    let horizon = default_horizon(automaton);
+    let mut evaluated_boards = 1;
+    let mut flips: Vec<Position> = vec![];
+    let mut progress = explore(automaton, horizon);
+
+    while progress.arrival.is_none() {
+        if flips.len() == max_flips {
+            return Err(format!("No repair with at most {} flips", max_flips).into());
+        }
+
+        let board = flipped(automaton, &flips);
+        let mut best: Option<(Progress, Position)> = None;
+
+        for cell in candidates(&board, &flips, progress.survived) {
+            let candidate = explore(&flipped(&board, &[cell]), horizon);
+            evaluated_boards += 1;
+
+            if best.is_none_or(|(best, _)| candidate.rank() < best.rank()) {
+                best = Some((candidate, cell));
+            }
+        }
+
+        let Some((next, cell)) = best else {
+            return Err("No cell can be flipped".into());
+        };
+
+        flips.push(cell);
+        progress = next;
+    }
+
+    // A later flip can make an earlier one useless
+    let mut index = 0;
+
+    while flips.len() > 1 && index < flips.len() {
+        let mut without = flips.clone();
+        without.remove(index);
+
+        evaluated_boards += 1;
+
+        if explore(&flipped(automaton, &without), horizon)
+            .arrival
+            .is_some()
+        {
+            flips = without;
+        } else {
+            index += 1;
+        }
+    }
+
+    flips.sort();
+
+    let repaired = flipped(automaton, &flips);
+    let result = solve(&repaired, &SolveOptions::default())?;
+
+    let Some(path) = result.path() else {
+        return Err(format!(
+            "No path found through the repaired board: {:?}",
+            result.outcome
+        )
+        .into());
+    };
+
+    Ok(Repair {
+        proven_minimum: flips.len() <= 2,
+        flips,
+        path: path.to_vec(),
+        automaton: repaired,
+        evaluated_boards,
+    })
+}
+
+/// `automaton` with `cells` switched between dead and alive.
+fn flipped(automaton: &Automaton, cells: &[Position]) -> Automaton {
+    let mut automaton = automaton.clone();
+
+    for (i, j) in cells {
+        automaton.matrix[*i][*j] = match automaton.matrix[*i][*j] {
+            CellState::Alive => CellState::Dead,
+            CellState::Dead => CellState::Alive,
+            state => state,
+        };
+    }
+
+    automaton
+}
+
+/// Cells worth flipping on `board`, whose reachable cells all die out after
+/// `survived` generations (or stay alive up to the horizon).
+///
+/// A flip changes a cell `g` generations later only if it is at most `g`
+/// rows and columns away, and the reachable cells of generation `g` are at
+/// most `g` moves from the start. Further flips cannot change anything before
+/// the reachable cells die out.
+fn candidates(board: &Automaton, flips: &[Position], survived: u32) -> Vec<Position> {
+    let (start_i, start_j) = board.starting_point;
+    let reach = 2 * survived as usize + 1;
+
+    let mut cells = vec![];
+
+    for (i, row) in board.matrix.iter().enumerate() {
+        for (j, state) in row.iter().enumerate() {
+            let flippable = matches!(state, CellState::Alive | CellState::Dead);
+
+            if flippable
+                && i.abs_diff(start_i).max(j.abs_diff(start_j)) <= reach
+                && !flips.contains(&(i, j))
+            {
+                cells.push((i, j));
+            }
+        }
+    }
+
+    cells
+}
+
+fn explore(automaton: &Automaton, horizon: u32) -> Progress {
+    let height = automaton.matrix.len();
+    let width = automaton.matrix[0].len();
+    let finishing_point = automaton.finishing_point;
+
+    let mut reachable = CellSet::new(height, width);
+    reachable.insert(automaton.starting_point);
+
+    let mut progress = Progress {
+        arrival: None,
+        closest: manhattan_distance(automaton.starting_point, finishing_point),
+        survived: 1,
+    };
+
+    let mut board = automaton.clone();
+
+    // The solvers precompute `horizon` generations, the first one included
+    for moves in 1..horizon {
+        board = board.iterate();
+        reachable = expand(&board.matrix, &reachable);
+
+        if reachable.is_empty() {
+            break;
+        }
+
+        progress.survived += 1;
+
+        for position in reachable.positions() {
+            progress.closest = progress
+                .closest
+                .min(manhattan_distance(position, finishing_point));
+        }
+
+        if reachable.contains(finishing_point) {
+            progress.arrival = Some(moves);
+            break;
+        }
+    }
+
+    progress
+}
@@ -41,6 +41,12 @@ impl SparseAutomaton {
             })
             .collect();
 
+        Ok(SparseAutomaton::from_integer_matrix(input))
+    }
+
+    /// Reads the same matrices as `Automaton::from_integer_matrix`. Without a
+    /// start or finish, they default to the top left and bottom right corners.
+    pub fn from_integer_matrix(input: Vec<Vec<i32>>) -> SparseAutomaton {
         let width = input[0].len();
         let height = input.len();
 
@@ -66,14 +72,14 @@ impl SparseAutomaton {
             }
         }
 
-        Ok(SparseAutomaton {
+        SparseAutomaton {
             width,
             height,
             live_cells,
             starting_point,
             finishing_point,
             generation: 1,
-        })
+        }
     }
 
     fn get_number_of_alive_neighbors(&self, (x, y): Position) -> u32 {
@@ -0,0 +1,11 @@
+use crate::automaton::Automaton;
+
+pub mod difficulty;
+pub mod generations;
+pub mod heatmap;
+
+/// Generations the solvers precompute for `automaton`, a sensible horizon for
+/// looking at how it evolves.
+pub fn default_horizon(automaton: &Automaton) -> u32 {
+    (2.5 * (automaton.matrix.len() + automaton.matrix[0].len()) as f32) as u32
+}
@@ -0,0 +1,90 @@
+use serde::Serialize;
+
+use crate::automaton::Automaton;
+use crate::path_finder::reachability::{expand, CellSet};
+use crate::path_finder::shared::manhattan_distance;
+
+/// Reachable cells at or below which a shrinking frontier is a bottleneck.
+pub const BOTTLENECK_CELLS: usize = 4;
+
+/// How hard it is to cross a board.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct Difficulty {
+    pub first_generation: u32,
+    pub manhattan_distance: u32,
+    /// Moves of the shortest path, None if the finish is not reached within
+    /// the horizon.
+    pub optimal_moves: Option<u32>,
+    /// Optimal moves over the Manhattan distance, 1 for a straight walk.
+    pub detour: Option<f64>,
+    /// Cells the particle can be at in each generation, from the first one
+    /// until the finish is reached, every cell is walled in, or the horizon.
+    pub reachable_states: Vec<usize>,
+    pub bottlenecks: Vec<Bottleneck>,
+    /// The detour, multiplied by one more than the number of bottlenecks.
+    /// None for boards without a path.
+    pub score: Option<f64>,
+}
+
+/// A generation where fewer cells than before are reachable, and only a few.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+pub struct Bottleneck {
+    pub generation: u32,
+    pub reachable_states: usize,
+}
+
+/// Rates `automaton` over `horizon` generations, its current one included, as
+/// many as the solvers would search with that many precomputed generations.
+///
+/// Steps the automaton and the set of reachable cells together, as the
+/// breadth first solver does, so only one generation is held at a time.
+pub fn rate(automaton: &Automaton, horizon: u32) -> Difficulty {
+    let height = automaton.matrix.len();
+    let width = automaton.matrix[0].len();
+
+    let mut reachable = CellSet::new(height, width);
+    reachable.insert(automaton.starting_point);
+
+    let mut reachable_states = vec![1];
+    let mut bottlenecks = vec![];
+    let mut optimal_moves = None;
+    let mut board = automaton.clone();
+
+    for moves in 1..horizon {
+        board = board.iterate();
+        reachable = expand(&board.matrix, &reachable);
+
+        let count = reachable.count();
+
+        if count < *reachable_states.last().unwrap() && count <= BOTTLENECK_CELLS {
+            bottlenecks.push(Bottleneck {
+                generation: board.generation,
+                reachable_states: count,
+            });
+        }
+
+        reachable_states.push(count);
+
+        if reachable.contains(automaton.finishing_point) {
+            optimal_moves = Some(moves);
+            break;
+        }
+
+        if count == 0 {
+            break;
+        }
+    }
+
+    let distance = manhattan_distance(automaton.starting_point, automaton.finishing_point);
+    let detour = optimal_moves.map(|moves| moves as f64 / distance as f64);
+
+    Difficulty {
+        first_generation: automaton.generation,
+        manhattan_distance: distance,
+        optimal_moves,
+        detour,
+        reachable_states,
+        score: detour.map(|detour| detour * (1 + bottlenecks.len()) as f64),
+        bottlenecks,
+    }
+}
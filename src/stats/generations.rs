@@ -0,0 +1,241 @@
+use std::io::{self, Write};
+use std::str::FromStr;
+
+use serde::Serialize;
+
+use crate::automaton::{Automaton, CellState};
+use crate::path_finder::shared::get_possible_moves;
+
+/// Numbers describing one generation of an automaton.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct GenerationStats {
+    pub generation: u32,
+    /// Alive cells.
+    pub population: usize,
+    /// Alive cells out of every cell of the board.
+    pub density: f64,
+    /// Cells alive in this generation and not in the previous one. None for
+    /// the first generation.
+    pub births: Option<usize>,
+    pub deaths: Option<usize>,
+    /// Smallest rectangle holding every alive cell, if any.
+    pub bounding_box: Option<BoundingBox>,
+    /// Whether every cell next to the start is alive, so the particle cannot
+    /// leave it.
+    pub start_blocked: bool,
+    /// Whether every cell next to the finish is alive, so the particle cannot
+    /// reach it.
+    pub finish_blocked: bool,
+}
+
+/// Rows and columns, both inclusive.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+pub struct BoundingBox {
+    pub top: usize,
+    pub left: usize,
+    pub bottom: usize,
+    pub right: usize,
+}
+
+/// Stats of consecutive generations, computed by stepping the automaton so
+/// only two generations are held at a time.
+pub struct Generations {
+    automaton: Automaton,
+    previous: Option<Vec<Vec<CellState>>>,
+    started: bool,
+    remaining: u32,
+}
+
+impl Generations {
+    /// `generations` generations of `automaton`, starting from its current one.
+    pub fn new(automaton: &Automaton, generations: u32) -> Self {
+        Generations {
+            automaton: automaton.clone(),
+            previous: None,
+            started: false,
+            remaining: generations,
+        }
+    }
+}
+
+impl Iterator for Generations {
+    type Item = GenerationStats;
+
+    fn next(&mut self) -> Option<GenerationStats> {
+        if self.remaining == 0 {
+            return None;
+        }
+
+        if self.started {
+            let next = self.automaton.iterate();
+            let previous = std::mem::replace(&mut self.automaton, next);
+            self.previous = Some(previous.matrix);
+        }
+
+        self.started = true;
+        self.remaining -= 1;
+
+        let matrix = &self.automaton.matrix;
+        let previous = self.previous.as_ref();
+
+        let mut population = 0;
+        let mut births = 0;
+        let mut deaths = 0;
+        let mut bounding_box: Option<BoundingBox> = None;
+
+        for (i, row) in matrix.iter().enumerate() {
+            for (j, cell) in row.iter().enumerate() {
+                let alive = *cell == CellState::Alive;
+                let was_alive = previous.is_some_and(|previous| previous[i][j] == CellState::Alive);
+
+                if alive {
+                    population += 1;
+
+                    bounding_box = Some(match bounding_box {
+                        Some(bounds) => BoundingBox {
+                            top: bounds.top.min(i),
+                            left: bounds.left.min(j),
+                            bottom: bounds.bottom.max(i),
+                            right: bounds.right.max(j),
+                        },
+                        None => BoundingBox {
+                            top: i,
+                            left: j,
+                            bottom: i,
+                            right: j,
+                        },
+                    });
+                }
+
+                if alive && !was_alive {
+                    births += 1;
+                }
+
+                if !alive && was_alive {
+                    deaths += 1;
+                }
+            }
+        }
+
+        let cells = matrix.len() * matrix[0].len();
+
+        Some(GenerationStats {
+            generation: self.automaton.generation,
+            population,
+            density: population as f64 / cells as f64,
+            births: previous.map(|_| births),
+            deaths: previous.map(|_| deaths),
+            bounding_box,
+            start_blocked: get_possible_moves(matrix, self.automaton.starting_point).is_empty(),
+            finish_blocked: get_possible_moves(matrix, self.automaton.finishing_point).is_empty(),
+        })
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub enum ReportFormat {
+    /// Aligned columns, for reading in a terminal.
+    #[default]
+    Table,
+    Csv,
+    /// A JSON array with one object per generation.
+    Json,
+}
+
+impl FromStr for ReportFormat {
+    type Err = String;
+
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        match name {
+            "table" => Ok(ReportFormat::Table),
+            "csv" => Ok(ReportFormat::Csv),
+            "json" => Ok(ReportFormat::Json),
+            _ => Err(format!("Unknown report format: {}", name)),
+        }
+    }
+}
+
+const COLUMNS: [&str; 8] = [
+    "generation",
+    "population",
+    "density",
+    "births",
+    "deaths",
+    "bounding_box",
+    "start_blocked",
+    "finish_blocked",
+];
+
+/// Writes each generation as soon as it is computed.
+pub fn write_report<W: Write>(
+    generations: impl Iterator<Item = GenerationStats>,
+    format: ReportFormat,
+    mut writer: W,
+) -> io::Result<()> {
+    match format {
+        ReportFormat::Table => writeln!(
+            writer,
+            "{:>10} {:>10} {:>8} {:>7} {:>7} {:>21} {:>13} {:>14}",
+            COLUMNS[0],
+            COLUMNS[1],
+            COLUMNS[2],
+            COLUMNS[3],
+            COLUMNS[4],
+            COLUMNS[5],
+            COLUMNS[6],
+            COLUMNS[7]
+        )?,
+        ReportFormat::Csv => writeln!(writer, "{}", COLUMNS.join(","))?,
+        ReportFormat::Json => write!(writer, "[")?,
+    }
+
+    for (index, stats) in generations.enumerate() {
+        let count = |count: Option<usize>| count.map_or(String::new(), |count| count.to_string());
+        let bounds = stats.bounding_box.map_or(String::new(), |bounds| {
+            format!(
+                "{}:{}-{}:{}",
+                bounds.top, bounds.left, bounds.bottom, bounds.right
+            )
+        });
+
+        match format {
+            ReportFormat::Table => writeln!(
+                writer,
+                "{:>10} {:>10} {:>8.4} {:>7} {:>7} {:>21} {:>13} {:>14}",
+                stats.generation,
+                stats.population,
+                stats.density,
+                count(stats.births),
+                count(stats.deaths),
+                bounds,
+                stats.start_blocked,
+                stats.finish_blocked
+            )?,
+            ReportFormat::Csv => writeln!(
+                writer,
+                "{},{},{},{},{},{},{},{}",
+                stats.generation,
+                stats.population,
+                stats.density,
+                count(stats.births),
+                count(stats.deaths),
+                bounds,
+                stats.start_blocked,
+                stats.finish_blocked
+            )?,
+            ReportFormat::Json => {
+                if index > 0 {
+                    write!(writer, ",")?;
+                }
+
+                write!(writer, "\n{}", serde_json::to_string(&stats)?)?;
+            }
+        }
+    }
+
+    if format == ReportFormat::Json {
+        writeln!(writer, "\n]")?;
+    }
+
+    writer.flush()
+}
@@ -0,0 +1,156 @@
+use std::io::{self, Write};
+
+use serde_json::{json, Value};
+
+use crate::automaton::{Automaton, CellState, Position};
+use crate::render::image::Image;
+use crate::render::{mix, RenderOptions};
+
+/// How often each cell is alive over a number of generations, and when it is
+/// first and last free.
+pub struct Heatmap {
+    pub first_generation: u32,
+    pub generations: u32,
+    pub height: usize,
+    pub width: usize,
+    // Every matrix is row by row
+    alive_counts: Vec<u32>,
+    first_free: Vec<Option<u32>>,
+    last_free: Vec<Option<u32>>,
+}
+
+impl Heatmap {
+    /// Accumulates `generations` generations of `automaton`, starting from its
+    /// current one. Only one generation is held at a time.
+    pub fn new(automaton: &Automaton, generations: u32) -> Self {
+        let height = automaton.matrix.len();
+        let width = automaton.matrix[0].len();
+
+        let mut heatmap = Heatmap {
+            first_generation: automaton.generation,
+            generations,
+            height,
+            width,
+            alive_counts: vec![0; height * width],
+            first_free: vec![None; height * width],
+            last_free: vec![None; height * width],
+        };
+
+        let mut automaton = automaton.clone();
+
+        for index in 0..generations {
+            if index > 0 {
+                automaton = automaton.iterate();
+            }
+
+            for (cell, state) in automaton.matrix.iter().flatten().enumerate() {
+                if *state == CellState::Alive {
+                    heatmap.alive_counts[cell] += 1;
+                } else {
+                    heatmap.first_free[cell].get_or_insert(automaton.generation);
+                    heatmap.last_free[cell] = Some(automaton.generation);
+                }
+            }
+        }
+
+        heatmap
+    }
+
+    pub fn alive_count(&self, (i, j): Position) -> u32 {
+        self.alive_counts[i * self.width + j]
+    }
+
+    /// Fraction of the generations in which the cell is alive.
+    pub fn occupancy(&self, position: Position) -> f64 {
+        if self.generations == 0 {
+            return 0.0;
+        }
+
+        self.alive_count(position) as f64 / self.generations as f64
+    }
+
+    pub fn first_free(&self, (i, j): Position) -> Option<u32> {
+        self.first_free[i * self.width + j]
+    }
+
+    pub fn last_free(&self, (i, j): Position) -> Option<u32> {
+        self.last_free[i * self.width + j]
+    }
+
+    /// The alive counts, one line per row of the board.
+    pub fn write_csv<W: Write>(&self, mut writer: W) -> io::Result<()> {
+        for row in self.alive_counts.chunks(self.width) {
+            let line: Vec<String> = row.iter().map(|count| count.to_string()).collect();
+
+            writeln!(writer, "{}", line.join(","))?;
+        }
+
+        writer.flush()
+    }
+
+    /// Alive counts and first and last free generations as matrices, `null`
+    /// for the cells that are never free.
+    pub fn to_json(&self) -> Value {
+        json!({
+            "first_generation": self.first_generation,
+            "generations": self.generations,
+            "alive_counts": self.alive_counts.chunks(self.width).collect::<Vec<_>>(),
+            "first_free": self.first_free.chunks(self.width).collect::<Vec<_>>(),
+            "last_free": self.last_free.chunks(self.width).collect::<Vec<_>>(),
+        })
+    }
+
+    /// Each cell shaded from the dead colour, for the least occupied cell, to
+    /// the alive colour, for the most occupied one, with the start and finish
+    /// in their own colours.
+    pub fn to_image(&self, automaton: &Automaton, options: &RenderOptions) -> Image {
+        let counts = (0..self.height)
+            .flat_map(|i| (0..self.width).map(move |j| (i, j)))
+            .filter(|position| {
+                *position != automaton.starting_point && *position != automaton.finishing_point
+            })
+            .map(|position| self.alive_count(position));
+
+        let least = counts.clone().min().unwrap_or(0);
+        let most = counts.max().unwrap_or(0);
+
+        let cell_size = options.cell_size;
+        let width = self.width * cell_size;
+        let height = self.height * cell_size;
+
+        let mut pixels = Vec::with_capacity(width * height * 3);
+
+        for i in 0..self.height {
+            let mut row = Vec::with_capacity(width * 3);
+
+            for j in 0..self.width {
+                let colour = if (i, j) == automaton.starting_point {
+                    options.start_colour
+                } else if (i, j) == automaton.finishing_point {
+                    options.finish_colour
+                } else {
+                    mix(
+                        options.dead_colour,
+                        options.alive_colour,
+                        (self.alive_count((i, j)) - least) as usize,
+                        (most - least).max(1) as usize,
+                    )
+                };
+
+                for _ in 0..cell_size {
+                    row.extend_from_slice(&colour);
+                }
+            }
+
+            for _ in 0..cell_size {
+                pixels.extend_from_slice(&row);
+            }
+        }
+
+        Image {
+            width,
+            height,
+            pixels,
+        }
+    }
+}
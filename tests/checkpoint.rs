@@ -0,0 +1,109 @@
+use stone_challenge::automaton::Automaton;
+use stone_challenge::path_finder::observer::SilentObserver;
+use stone_challenge::path_finder::{
+    find_path, find_path_with_checkpoint, Checkpointing, Interrupt, SearchLimits, Solver,
+};
+
+#[test]
+fn resumed_search_returns_the_same_path() {
+    let automaton = Automaton::from_file("inputs/second_challenge.json").unwrap();
+
+    let checkpoint_path = format!(
+        "{}/second_challenge.checkpoint.json",
+        env!("CARGO_TARGET_TMPDIR")
+    );
+
+    let uninterrupted = find_path(automaton.clone(), Solver::AStar).unwrap();
+
+    // Leaves behind the last checkpoint taken before the finish was reached
+    let checkpointed = find_path_with_checkpoint(
+        automaton.clone(),
+        &Checkpointing {
+            path: checkpoint_path.clone(),
+            interval: 1000,
+            resume: false,
+        },
+        SearchLimits::default(),
+        &Interrupt::new(),
+        &mut SilentObserver,
+    )
+    .unwrap()
+    .path()
+    .unwrap();
+
+    let resumed = find_path_with_checkpoint(
+        automaton.clone(),
+        &Checkpointing {
+            path: checkpoint_path,
+            interval: 1000,
+            resume: true,
+        },
+        SearchLimits::default(),
+        &Interrupt::new(),
+        &mut SilentObserver,
+    )
+    .unwrap()
+    .path()
+    .unwrap();
+
+    assert_eq!(checkpointed, uninterrupted);
+    assert_eq!(resumed, uninterrupted);
+}
+
+#[test]
+fn checkpoint_of_another_input_is_rejected() {
+    let checkpoint_path = format!("{}/sample.checkpoint.json", env!("CARGO_TARGET_TMPDIR"));
+
+    let checkpointing = Checkpointing {
+        path: checkpoint_path,
+        interval: 1,
+        resume: false,
+    };
+
+    find_path_with_checkpoint(
+        Automaton::from_file("inputs/sample.json").unwrap(),
+        &checkpointing,
+        SearchLimits::default(),
+        &Interrupt::new(),
+        &mut SilentObserver,
+    )
+    .unwrap();
+
+    // Same size, start and finish, one generation later
+    let next_generation = Automaton::from_file("inputs/sample.json")
+        .unwrap()
+        .iterate();
+
+    let error = find_path_with_checkpoint(
+        next_generation,
+        &Checkpointing {
+            resume: true,
+            ..checkpointing
+        },
+        SearchLimits::default(),
+        &Interrupt::new(),
+        &mut SilentObserver,
+    )
+    .unwrap_err();
+
+    assert!(error
+        .to_string()
+        .contains("Checkpoint was saved for another input"));
+}
+
+#[test]
+fn missing_checkpoint_is_an_error() {
+    let outcome = find_path_with_checkpoint(
+        Automaton::from_file("inputs/sample.json").unwrap(),
+        &Checkpointing {
+            path: format!("{}/missing.checkpoint.json", env!("CARGO_TARGET_TMPDIR")),
+            interval: 1,
+            resume: true,
+        },
+        SearchLimits::default(),
+        &Interrupt::new(),
+        &mut SilentObserver,
+    );
+
+    assert!(outcome.is_err());
+}
@@ -0,0 +1,149 @@
+use stone_challenge::automaton::{Automaton, CellState};
+use stone_challenge::generator::{generate, GenerateOptions, Placement, Solvability};
+use stone_challenge::path_finder::verification::verify_path;
+use stone_challenge::path_finder::SearchLimits;
+use stone_challenge::SolveOptions;
+
+fn alive_cells(automaton: &Automaton) -> usize {
+    automaton
+        .matrix
+        .iter()
+        .flatten()
+        .filter(|cell| **cell == CellState::Alive)
+        .count()
+}
+
+#[test]
+fn generated_boards_depend_only_on_the_options() {
+    let options = GenerateOptions {
+        density: 0.3,
+        seed: 11,
+        ..GenerateOptions::new(40, 50)
+    };
+
+    let board = generate(&options).unwrap().automaton;
+    let again = generate(&options).unwrap().automaton;
+    let other = generate(&GenerateOptions {
+        seed: 12,
+        ..options
+    })
+    .unwrap()
+    .automaton;
+
+    assert_eq!(board.matrix, again.matrix);
+    assert_ne!(board.matrix, other.matrix);
+    assert_eq!((board.matrix.len(), board.matrix[0].len()), (40, 50));
+    assert_eq!(board.generation, 1);
+    assert_eq!(board.starting_point, (0, 0));
+    assert_eq!(board.finishing_point, (39, 49));
+
+    // About 30% of the 2000 cells
+    assert!((500..700).contains(&alive_cells(&board)));
+
+    let empty = generate(&GenerateOptions {
+        density: 0.0,
+        ..GenerateOptions::new(5, 5)
+    })
+    .unwrap();
+
+    assert_eq!(alive_cells(&empty.automaton), 0);
+}
+
+#[test]
+fn start_and_finish_are_placed_as_configured() {
+    let fixed = generate(&GenerateOptions {
+        placement: Placement::Fixed {
+            start: (3, 1),
+            finish: (0, 4),
+        },
+        ..GenerateOptions::new(6, 6)
+    })
+    .unwrap()
+    .automaton;
+
+    assert_eq!(fixed.starting_point, (3, 1));
+    assert_eq!(fixed.finishing_point, (0, 4));
+
+    for seed in 0..20 {
+        let random = generate(&GenerateOptions {
+            seed,
+            placement: Placement::Random,
+            ..GenerateOptions::new(2, 3)
+        })
+        .unwrap()
+        .automaton;
+
+        assert_ne!(random.starting_point, random.finishing_point);
+    }
+
+    assert!(generate(&GenerateOptions {
+        placement: Placement::Fixed {
+            start: (6, 0),
+            finish: (0, 0),
+        },
+        ..GenerateOptions::new(6, 6)
+    })
+    .is_err());
+    assert!(generate(&GenerateOptions {
+        density: 1.5,
+        ..GenerateOptions::new(6, 6)
+    })
+    .is_err());
+    assert!(generate(&GenerateOptions::new(1, 1)).is_err());
+}
+
+#[test]
+fn solvable_boards_come_with_a_verified_path() {
+    let generated = generate(&GenerateOptions {
+        density: 0.35,
+        seed: 3,
+        solvable: Some(Solvability::default()),
+        ..GenerateOptions::new(15, 20)
+    })
+    .unwrap();
+
+    let path = generated.solution.as_ref().unwrap().path().unwrap();
+
+    assert!(verify_path(&generated.automaton, path).is_valid());
+    assert!(generated.attempts >= 1);
+
+    // A search that cannot expand a single state never solves a board
+    let hopeless = generate(&GenerateOptions {
+        solvable: Some(Solvability {
+            solve: SolveOptions {
+                limits: SearchLimits {
+                    expanded_states: Some(0),
+                    ..SearchLimits::default()
+                },
+                ..SolveOptions::default()
+            },
+            attempts: 3,
+        }),
+        ..GenerateOptions::new(10, 10)
+    });
+
+    assert!(hopeless.is_err());
+}
+
+#[test]
+fn boards_are_written_in_the_input_format() {
+    let automaton = generate(&GenerateOptions {
+        seed: 5,
+        placement: Placement::Random,
+        ..GenerateOptions::new(9, 13)
+    })
+    .unwrap()
+    .automaton;
+
+    let file_path = std::env::temp_dir().join("stone_challenge_generated_board.json");
+    automaton
+        .write_json(std::fs::File::create(&file_path).unwrap())
+        .unwrap();
+
+    let loaded = Automaton::from_file(file_path.to_str().unwrap()).unwrap();
+    std::fs::remove_file(&file_path).unwrap();
+
+    assert_eq!(loaded.matrix, automaton.matrix);
+    assert_eq!(loaded.starting_point, automaton.starting_point);
+    assert_eq!(loaded.finishing_point, automaton.finishing_point);
+}
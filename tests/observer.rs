@@ -0,0 +1,121 @@
+use serde_json::Value;
+use stone_challenge::automaton::{Automaton, Position};
+use stone_challenge::path_finder::observer::{JsonLinesObserver, SearchObserver, SearchStats};
+use stone_challenge::path_finder::{
+    find_path_with_observer, Interrupt, SearchLimits, SearchOutcome, Solver,
+};
+
+#[derive(Default)]
+struct Events {
+    expansions: Vec<u64>,
+    generations: Vec<u32>,
+    improvements: usize,
+    weights: Vec<f32>,
+    finish: Option<(SearchOutcome, SearchStats)>,
+}
+
+impl SearchObserver for Events {
+    fn on_expand(&mut self, stats: &SearchStats) {
+        self.expansions.push(stats.states_expanded);
+    }
+
+    fn on_generation_computed(&mut self, generation: u32) {
+        self.generations.push(generation);
+    }
+
+    fn on_improvement(&mut self, _path: &[Position]) {
+        self.improvements += 1;
+    }
+
+    fn on_heuristic_weight(&mut self, weight: f32) {
+        self.weights.push(weight);
+    }
+
+    fn on_finish(&mut self, outcome: &SearchOutcome, stats: &SearchStats) {
+        self.finish = Some((outcome.clone(), stats.clone()));
+    }
+}
+
+#[test]
+fn observer_sees_every_event_and_the_final_stats() {
+    let automaton = Automaton::from_file("inputs/first_challenge.json").unwrap();
+
+    for solver in [
+        "astar",
+        "anytime",
+        "beam",
+        "bfs",
+        "bidirectional",
+        "original",
+        "pruned",
+    ] {
+        let mut events = Events::default();
+
+        let outcome = find_path_with_observer(
+            automaton.clone(),
+            solver.parse().unwrap(),
+            SearchLimits::default(),
+            &Interrupt::new(),
+            &mut events,
+        );
+
+        let (finish_outcome, stats) = events.finish.unwrap();
+
+        assert_eq!(finish_outcome, outcome, "{}", solver);
+        assert!(matches!(outcome, SearchOutcome::Found(_)), "{}", solver);
+        assert!(events.improvements >= 1, "{}", solver);
+        assert!(!events.generations.is_empty(), "{}", solver);
+        assert!(
+            events.expansions.windows(2).all(|pair| pair[0] <= pair[1]),
+            "{}",
+            solver
+        );
+        assert_eq!(
+            events.expansions.last(),
+            Some(&stats.states_expanded),
+            "{}",
+            solver
+        );
+        assert!(stats.states_expanded > 0, "{}", solver);
+        assert!(stats.peak_open_list_size > 0, "{}", solver);
+        assert!(stats.peak_memory_bytes > 0, "{}", solver);
+
+        if solver == "anytime" {
+            assert_eq!(events.weights.first(), Some(&5.0));
+            assert!(events.weights.windows(2).all(|pair| pair[1] < pair[0]));
+        } else {
+            assert!(events.weights.is_empty(), "{}", solver);
+        }
+    }
+}
+
+#[test]
+fn json_lines_observer_writes_one_event_per_line() {
+    let automaton = Automaton::from_file("inputs/first_challenge.json").unwrap();
+
+    let mut output: Vec<u8> = vec![];
+
+    find_path_with_observer(
+        automaton,
+        Solver::AStar,
+        SearchLimits::default(),
+        &Interrupt::new(),
+        &mut JsonLinesObserver::new(&mut output),
+    );
+
+    let events: Vec<Value> = String::from_utf8(output)
+        .unwrap()
+        .lines()
+        .map(|line| serde_json::from_str(line).unwrap())
+        .collect();
+
+    let finish = events.last().unwrap();
+
+    assert_eq!(finish["event"], "finish");
+    assert_eq!(finish["outcome"], "found");
+    assert_eq!(finish["path_length"], 28);
+    assert!(events.iter().any(|event| event["event"] == "improvement"));
+    assert!(events
+        .iter()
+        .any(|event| event["event"] == "generation_computed"));
+}
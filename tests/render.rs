@@ -0,0 +1,328 @@
+use crossterm::event::KeyCode;
+use gif::{ColorOutput, DecodeOptions};
+use stone_challenge::automaton::{Automaton, CellState};
+use stone_challenge::render::animation::write_gif;
+use stone_challenge::render::image::{draw, write_contact_sheet, write_png, View, SHEET_GAP};
+use stone_challenge::render::svg::write_svg;
+use stone_challenge::render::terminal::{Replay, SNAPSHOT_STEP};
+use stone_challenge::render::{parse_colour, Frames, RenderOptions, Tile};
+use stone_challenge::{solve, SolveOptions};
+
+#[test]
+fn frames_follow_the_path_over_the_evolving_board() {
+    let automaton = Automaton::from_file("inputs/sample.json").unwrap();
+    let result = solve(&automaton, &SolveOptions::default()).unwrap();
+    let path = result.path().unwrap();
+
+    let frames: Vec<_> = Frames::new(&automaton, path).collect();
+
+    assert_eq!(frames.len(), path.len());
+
+    for (step, frame) in frames.iter().enumerate() {
+        assert_eq!(frame.step, step);
+        assert_eq!(frame.generation, automaton.generation + step as u32);
+        assert_eq!(frame.tile(path[step]), Tile::Particle);
+
+        for earlier in &path[..step] {
+            assert_ne!(frame.tile(*earlier), Tile::Dead);
+        }
+    }
+
+    assert_eq!(frames[1].tile(automaton.starting_point), Tile::Start);
+}
+
+#[test]
+fn gif_has_one_frame_per_step_in_the_chosen_colours() {
+    let automaton = Automaton::from_file("inputs/sample.json").unwrap();
+    let result = solve(&automaton, &SolveOptions::default()).unwrap();
+    let path = result.path().unwrap();
+
+    let options = RenderOptions {
+        cell_size: 3,
+        particle_colour: parse_colour("#ff00ff").unwrap(),
+        ..RenderOptions::default()
+    };
+
+    let mut output: Vec<u8> = vec![];
+    write_gif(&automaton, path, &options, &mut output).unwrap();
+
+    let mut decode_options = DecodeOptions::new();
+    decode_options.set_color_output(ColorOutput::RGBA);
+    let mut decoder = decode_options.read_info(output.as_slice()).unwrap();
+
+    let height = automaton.matrix.len();
+    let width = automaton.matrix[0].len();
+
+    assert_eq!(decoder.width() as usize, width * 3);
+    assert_eq!(decoder.height() as usize, height * 3);
+
+    let mut frames = 0;
+
+    while let Some(frame) = decoder.read_next_frame().unwrap() {
+        let (i, j) = path[frames];
+        let pixel = ((i * 3 + 1) * width * 3 + j * 3 + 1) * 4;
+
+        assert_eq!(&frame.buffer[pixel..pixel + 3], &[255, 0, 255]);
+
+        frames += 1;
+    }
+
+    assert_eq!(frames, path.len());
+}
+
+#[test]
+fn png_frames_can_be_cropped_around_the_particle_and_downsampled() {
+    let automaton = Automaton::from_file("inputs/second_challenge.json").unwrap();
+    let result = solve(&automaton, &SolveOptions::default()).unwrap();
+    let path = result.path().unwrap();
+
+    let height = automaton.matrix.len();
+    let width = automaton.matrix[0].len();
+
+    let options = RenderOptions {
+        cell_size: 1,
+        ..RenderOptions::default()
+    };
+
+    for frame in Frames::new(&automaton, path) {
+        let (i, j) = frame.position;
+
+        let full = draw(&frame, &View::default(), &options);
+        assert_eq!((full.width, full.height), (width, height));
+        assert_eq!(full.pixel(j, i), options.particle_colour);
+
+        let cropped = draw(
+            &frame,
+            &View {
+                crop_radius: Some(10),
+                ..View::default()
+            },
+            &options,
+        );
+        assert_eq!((cropped.width, cropped.height), (21, 21));
+        assert!(cropped
+            .pixels
+            .chunks(3)
+            .any(|pixel| pixel == options.particle_colour));
+
+        let downsampled = draw(
+            &frame,
+            &View {
+                downsample: 4,
+                ..View::default()
+            },
+            &options,
+        );
+        assert_eq!(
+            (downsampled.width, downsampled.height),
+            (width.div_ceil(4), height.div_ceil(4))
+        );
+        assert_eq!(downsampled.pixel(j / 4, i / 4), options.particle_colour);
+    }
+
+    let frame = Frames::new(&automaton, path).next().unwrap();
+    let mut output: Vec<u8> = vec![];
+    write_png(&draw(&frame, &View::default(), &options), &mut output).unwrap();
+
+    let decoder = png::Decoder::new(output.as_slice());
+    let reader = decoder.read_info().unwrap();
+
+    assert_eq!(reader.info().width as usize, width);
+    assert_eq!(reader.info().height as usize, height);
+}
+
+#[test]
+fn contact_sheet_holds_every_nth_step_and_the_last_one() {
+    let automaton = Automaton::from_file("inputs/sample.json").unwrap();
+    let result = solve(&automaton, &SolveOptions::default()).unwrap();
+    let path = result.path().unwrap();
+
+    let options = RenderOptions {
+        cell_size: 2,
+        ..RenderOptions::default()
+    };
+    let view = View::default();
+
+    let mut output: Vec<u8> = vec![];
+    write_contact_sheet(&automaton, path, 3, 4, &view, &options, &mut output).unwrap();
+
+    let decoder = png::Decoder::new(output.as_slice());
+    let mut reader = decoder.read_info().unwrap();
+    let mut pixels = vec![0; reader.output_buffer_size()];
+    reader.next_frame(&mut pixels).unwrap();
+
+    // Steps 0, 3, ..., 18 and 19
+    let thumbnails = path.len().div_ceil(3) + 1;
+    let rows = thumbnails.div_ceil(4);
+    let thumbnail_width = automaton.matrix[0].len() * 2;
+    let thumbnail_height = automaton.matrix.len() * 2;
+
+    let sheet_width = 4 * thumbnail_width + 5 * SHEET_GAP;
+    let sheet_height = rows * thumbnail_height + (rows + 1) * SHEET_GAP;
+
+    assert_eq!(reader.info().width as usize, sheet_width);
+    assert_eq!(reader.info().height as usize, sheet_height);
+
+    // The last thumbnail shows the particle on the finish
+    let last = thumbnails - 1;
+    let (i, j) = automaton.finishing_point;
+    let x = SHEET_GAP + (last % 4) * (thumbnail_width + SHEET_GAP) + j * 2;
+    let y = SHEET_GAP + (last / 4) * (thumbnail_height + SHEET_GAP) + i * 2;
+    let pixel = (y * sheet_width + x) * 3;
+
+    assert_eq!(&pixels[pixel..pixel + 3], &options.particle_colour);
+}
+
+#[test]
+fn replay_steps_back_to_the_same_boards() {
+    let automaton = Automaton::from_file("inputs/second_challenge.json").unwrap();
+    let result = solve(&automaton, &SolveOptions::default()).unwrap();
+    let path = result.path().unwrap();
+
+    let mut replay = Replay::new(&automaton, path);
+    let boards: Vec<_> = Frames::new(&automaton, path)
+        .take(3 * SNAPSHOT_STEP)
+        .map(|frame| frame.tiles)
+        .collect();
+
+    replay.go_to(boards.len() - 1);
+
+    for step in (0..boards.len()).rev() {
+        assert_eq!(replay.step(), step);
+        assert_eq!(replay.particle(), path[step]);
+        assert_eq!(
+            replay.board().generation,
+            automaton.generation + step as u32
+        );
+
+        for (index, row) in replay.board().matrix.iter().enumerate() {
+            for (column, cell) in row.iter().enumerate() {
+                let alive = boards[step][index * row.len() + column] == Tile::Alive;
+                assert!(alive == (*cell == CellState::Alive) || (index, column) == path[step]);
+            }
+        }
+
+        assert!(replay.handle_key(KeyCode::Left));
+    }
+
+    assert_eq!(replay.step(), 0);
+    assert!(!replay.handle_key(KeyCode::Char('q')));
+}
+
+#[test]
+fn replay_cursor_shows_the_neighbours_and_the_next_state() {
+    let automaton = Automaton::from_file("inputs/sample.json").unwrap();
+    let result = solve(&automaton, &SolveOptions::default()).unwrap();
+    let path = result.path().unwrap();
+
+    let mut replay = Replay::new(&automaton, path);
+    replay.handle_key(KeyCode::End);
+    replay.handle_key(KeyCode::Char('c'));
+
+    assert_eq!(replay.step(), path.len() - 1);
+    assert_eq!(replay.distance_to_finish(), 0);
+    assert_eq!(replay.cursor, automaton.finishing_point);
+
+    for key in ['k', 'k', 'h'] {
+        replay.handle_key(KeyCode::Char(key));
+    }
+
+    let (i, j) = automaton.finishing_point;
+    let cursor = (i - 2, j - 1);
+    let board = replay.board();
+    let info = replay.cursor_info();
+
+    assert!(!replay.follow);
+    assert_eq!(info.position, cursor);
+    assert_eq!(info.state, board.matrix[cursor.0][cursor.1]);
+    assert_eq!(
+        info.alive_neighbours,
+        board.get_number_of_alive_neighbours(cursor)
+    );
+    assert_eq!(
+        info.alive_next,
+        board.iterate().matrix[cursor.0][cursor.1] == CellState::Alive
+    );
+}
+
+#[test]
+fn svg_draws_every_step_of_the_path_over_the_selected_generation() {
+    let automaton = Automaton::from_file("inputs/sample.json").unwrap();
+    let result = solve(&automaton, &SolveOptions::default()).unwrap();
+    let path = result.path().unwrap();
+
+    let mut output: Vec<u8> = vec![];
+    write_svg(&automaton, path, 6, &RenderOptions::default(), &mut output).unwrap();
+    let svg = String::from_utf8(output).unwrap();
+
+    let alive_cells = automaton
+        .advance_to(6)
+        .matrix
+        .iter()
+        .flatten()
+        .filter(|cell| **cell == CellState::Alive)
+        .count();
+
+    assert!(svg.starts_with("<svg"));
+    assert!(svg.trim_end().ends_with("</svg>"));
+    assert!(svg.contains("<title>Generation 6</title>"));
+    assert_eq!(svg.matches(r#"class="step""#).count(), path.len() - 1);
+    assert_eq!(svg.matches(r#"class="start""#).count(), 1);
+    assert_eq!(svg.matches(r#"class="finish""#).count(), 1);
+    assert!(svg.contains("<title>Step 5, generation 6</title></circle>"));
+    assert_eq!(svg.matches(r#"class="collision""#).count(), 0);
+    assert!((1..=alive_cells).contains(&svg.matches("<rect x=").count()));
+}
+
+#[test]
+fn svg_marks_collisions() {
+    let automaton = Automaton::from_file("inputs/sample.json").unwrap();
+    let next = automaton.iterate();
+
+    let alive = (0..next.matrix.len())
+        .flat_map(|i| (0..next.matrix[0].len()).map(move |j| (i, j)))
+        .find(|(i, j)| next.matrix[*i][*j] == CellState::Alive)
+        .unwrap();
+
+    let path = [automaton.starting_point, alive];
+
+    let mut output: Vec<u8> = vec![];
+    write_svg(&automaton, &path, 1, &RenderOptions::default(), &mut output).unwrap();
+    let svg = String::from_utf8(output).unwrap();
+
+    assert_eq!(svg.matches(r#"class="collision""#).count(), 1);
+    assert!(svg.contains("<title>Collision at step 1, generation 2</title>"));
+    assert!(write_svg(&next, &path, 1, &RenderOptions::default(), vec![]).is_err());
+}
+
+#[test]
+fn colours_are_parsed_from_hex() {
+    assert_eq!(parse_colour("#1a2B3c"), Ok([0x1a, 0x2b, 0x3c]));
+    assert_eq!(parse_colour("ffffff"), Ok([255, 255, 255]));
+    assert!(parse_colour("#fff").is_err());
+    assert!(parse_colour("#gggggg").is_err());
+}
+
+/// Takes every byte, but fails to flush them.
+struct UnflushableWriter;
+
+impl std::io::Write for UnflushableWriter {
+    fn write(&mut self, buffer: &[u8]) -> std::io::Result<usize> {
+        Ok(buffer.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Err(std::io::Error::other("disk full"))
+    }
+}
+
+#[test]
+fn renderers_report_errors_when_flushing() {
+    let automaton = Automaton::from_file("inputs/sample.json").unwrap();
+    let result = solve(&automaton, &SolveOptions::default()).unwrap();
+    let path = result.path().unwrap();
+    let options = RenderOptions::default();
+
+    assert!(write_gif(&automaton, path, &options, UnflushableWriter).is_err());
+    assert!(write_svg(&automaton, path, 1, &options, UnflushableWriter).is_err());
+}
@@ -0,0 +1,62 @@
+use stone_challenge::automaton::Automaton;
+use stone_challenge::path_finder::verification::verify_path;
+use stone_challenge::repair::repair;
+use stone_challenge::{solve, SolveOptions};
+
+/// The start is walled in by a block whose cells next to it survive the
+/// first generation.
+fn walled_in() -> Automaton {
+    let mut matrix = vec![vec![0; 8]; 7];
+
+    for row in matrix.iter_mut().take(3) {
+        row[..3].fill(1);
+    }
+
+    matrix[0][0] = 2;
+    matrix[6][7] = 3;
+
+    Automaton::from_integer_matrix(matrix)
+}
+
+#[test]
+fn repair_flips_the_fewest_cells_for_a_path() {
+    let automaton = walled_in();
+
+    assert!(solve(&automaton, &SolveOptions::default())
+        .unwrap()
+        .path()
+        .is_none());
+
+    let repair = repair(&automaton, 3).unwrap();
+
+    assert_eq!(repair.flips.len(), 1);
+    assert!(repair.proven_minimum);
+
+    for (i, row) in automaton.matrix.iter().enumerate() {
+        for (j, state) in row.iter().enumerate() {
+            let changed = repair.automaton.matrix[i][j] != *state;
+
+            assert_eq!(changed, repair.flips.contains(&(i, j)));
+        }
+    }
+
+    assert!(verify_path(&repair.automaton, &repair.path).is_valid());
+    assert_eq!(
+        solve(&repair.automaton, &SolveOptions::default())
+            .unwrap()
+            .length(),
+        Some(repair.path.len())
+    );
+}
+
+#[test]
+fn repair_leaves_solvable_boards_alone_and_respects_the_flip_limit() {
+    let sample = Automaton::from_file("inputs/sample.json").unwrap();
+    let repair_of_sample = repair(&sample, 0).unwrap();
+
+    assert!(repair_of_sample.flips.is_empty());
+    assert_eq!(repair_of_sample.automaton.matrix, sample.matrix);
+    assert_eq!(repair_of_sample.path.len(), 20);
+
+    assert!(repair(&walled_in(), 0).is_err());
+}
@@ -0,0 +1,189 @@
+use stone_challenge::automaton::{Automaton, CellState};
+use stone_challenge::path_finder::verification::{verify_path, Collision};
+use stone_challenge::path_finder::{Limit, SearchLimits, SearchOutcome, Solver, Start};
+use stone_challenge::{solve, SolveOptions};
+
+fn load(input: &str) -> Automaton {
+    Automaton::from_file(&format!("inputs/{}.json", input)).unwrap()
+}
+
+#[test]
+fn solve_returns_a_verified_path_with_its_stats() {
+    let automaton = load("first_challenge");
+
+    for solver in [Solver::AStar, Solver::Bfs, Solver::Anytime] {
+        let result = solve(
+            &automaton,
+            &SolveOptions {
+                solver,
+                ..SolveOptions::default()
+            },
+        )
+        .unwrap();
+
+        let report = result.verification.as_ref().unwrap();
+
+        assert_eq!(result.length(), Some(28), "{:?}", solver);
+        assert!(report.is_valid(), "{:?}", solver);
+        assert_eq!(report.length, 28, "{:?}", solver);
+        assert!(result.stats.states_expanded > 0, "{:?}", solver);
+        assert!(result.elapsed() >= result.stats.search_time, "{:?}", solver);
+    }
+}
+
+#[test]
+fn solve_reports_the_exceeded_limit() {
+    let automaton = load("second_challenge");
+
+    let result = solve(
+        &automaton,
+        &SolveOptions {
+            limits: SearchLimits {
+                expanded_states: Some(100),
+                ..SearchLimits::default()
+            },
+            ..SolveOptions::default()
+        },
+    )
+    .unwrap();
+
+    assert!(matches!(
+        result.outcome,
+        SearchOutcome::LimitExceeded {
+            which: Limit::ExpandedStates,
+            partial: Some(_),
+        }
+    ));
+    assert_eq!(result.path(), None);
+    assert_eq!(result.verification, None);
+}
+
+#[test]
+fn solve_starts_from_any_generation_and_position() {
+    let automaton = load("first_challenge");
+
+    let optimum = solve(&automaton, &SolveOptions::default()).unwrap();
+    let optimum = optimum.path().unwrap();
+
+    // Any piece of an optimal path is optimal too
+    let step = 10;
+    let start = Start {
+        generation: automaton.generation + step as u32,
+        position: optimum[step],
+    };
+
+    for solver in [
+        Solver::AStar,
+        Solver::Bfs,
+        Solver::Bidirectional,
+        Solver::Anytime,
+    ] {
+        let result = solve(
+            &automaton,
+            &SolveOptions {
+                solver,
+                start: Some(start),
+                ..SolveOptions::default()
+            },
+        )
+        .unwrap();
+
+        let path = result.path().unwrap();
+
+        assert_eq!(path.len(), optimum.len() - step, "{:?}", solver);
+        assert_eq!(path[0], start.position, "{:?}", solver);
+        assert!(
+            verify_path(&start.apply(&automaton).unwrap(), path).is_valid(),
+            "{:?}",
+            solver
+        );
+    }
+}
+
+#[test]
+fn solve_rejects_invalid_starts() {
+    let automaton = load("sample");
+
+    for (start, error) in [
+        (
+            Start {
+                generation: automaton.generation,
+                position: (100, 100),
+            },
+            "is outside the 10x9 board",
+        ),
+        (
+            Start {
+                generation: automaton.generation - 1,
+                position: automaton.starting_point,
+            },
+            "before the current generation",
+        ),
+        (
+            Start {
+                generation: automaton.generation,
+                position: (3, 4),
+            },
+            "alive at generation",
+        ),
+    ] {
+        let options = SolveOptions {
+            start: Some(start),
+            ..SolveOptions::default()
+        };
+
+        let message = solve(&automaton, &options).unwrap_err().to_string();
+
+        assert!(message.contains(error), "{:?}: {}", start, message);
+    }
+}
+
+#[test]
+fn verification_report_lists_every_problem() {
+    let automaton = load("first_challenge");
+    let path = solve(&automaton, &SolveOptions::default())
+        .unwrap()
+        .path()
+        .unwrap()
+        .to_vec();
+
+    let valid = verify_path(&automaton, &path);
+    assert!(valid.is_valid());
+
+    // Staying in place is not a move, and the last step is off the board
+    let mut broken = path.clone();
+    broken[3] = broken[2];
+    broken.push((automaton.matrix.len(), 0));
+
+    let report = verify_path(&automaton, &broken);
+
+    assert!(!report.is_valid());
+    assert!(report.starts_at_start);
+    assert!(!report.ends_at_finish);
+    assert!(report.invalid_moves.contains(&3));
+    assert_eq!(report.out_of_bounds, vec![path.len()]);
+
+    // Teleports step 5 onto an alive cell of its generation
+    let mut generation = automaton.clone();
+
+    for _ in 0..5 {
+        generation = generation.iterate();
+    }
+
+    let alive = (0..generation.matrix.len())
+        .flat_map(|i| (0..generation.matrix[0].len()).map(move |j| (i, j)))
+        .find(|(i, j)| generation.matrix[*i][*j] == CellState::Alive)
+        .unwrap();
+
+    let mut collided = path.clone();
+    collided[5] = alive;
+
+    assert_eq!(
+        verify_path(&automaton, &collided).collisions,
+        vec![Collision {
+            step: 5,
+            generation: 6,
+            position: alive,
+        }]
+    );
+}
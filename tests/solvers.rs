@@ -0,0 +1,346 @@
+use stone_challenge::automaton::{Automaton, Position};
+use stone_challenge::path_finder::beam::BeamOptions;
+use stone_challenge::path_finder::observer::{SearchObserver, SilentObserver};
+use stone_challenge::path_finder::original::DfsOptions;
+use stone_challenge::path_finder::shortening::shorten_path;
+use stone_challenge::path_finder::{
+    find_path, find_path_with_observer, verify_result, Interrupt, Limit, SearchLimits,
+    SearchOutcome, Solver,
+};
+use stone_challenge::{solve, SolveOptions};
+
+const INPUTS: [&str; 3] = ["sample", "first_challenge", "second_challenge"];
+
+fn load(input: &str) -> Automaton {
+    Automaton::from_file(&format!("inputs/{}.json", input)).unwrap()
+}
+
+/// Board on which the particle can move until the last precomputed
+/// generation without ever reaching the finish.
+fn unreachable_finish() -> Automaton {
+    Automaton::from_integer_matrix(vec![
+        vec![2, 0, 1, 0, 1, 0, 1],
+        vec![0, 0, 0, 0, 1, 1, 0],
+        vec![1, 1, 1, 0, 1, 0, 1],
+        vec![0, 1, 0, 0, 1, 0, 0],
+        vec![1, 0, 1, 0, 0, 0, 1],
+        vec![1, 0, 0, 0, 0, 1, 3],
+    ])
+}
+
+#[derive(Default)]
+struct Improvements(Vec<Vec<Position>>);
+
+impl SearchObserver for Improvements {
+    fn on_improvement(&mut self, path: &[Position]) {
+        self.0.push(path.to_vec());
+    }
+}
+
+#[test]
+fn bidirectional_matches_forward_bfs_optimum() {
+    for input in INPUTS {
+        let automaton = load(input);
+
+        let optimum = find_path(automaton.clone(), Solver::Bfs).unwrap();
+        let path = find_path(automaton.clone(), Solver::Bidirectional).unwrap();
+
+        assert!(verify_result(&automaton, &path));
+        assert_eq!(path.first(), Some(&automaton.starting_point));
+        assert_eq!(path.last(), Some(&automaton.finishing_point));
+        assert_eq!(path.len(), optimum.len(), "{}", input);
+    }
+}
+
+#[test]
+fn astar_matches_forward_bfs_optimum() {
+    for input in INPUTS {
+        let automaton = load(input);
+
+        let optimum = find_path(automaton.clone(), Solver::Bfs).unwrap();
+        let path = find_path(automaton.clone(), Solver::AStar).unwrap();
+
+        assert_eq!(path.len(), optimum.len(), "{}", input);
+    }
+}
+
+#[test]
+fn pruning_dead_ends_keeps_the_optimum_with_fewer_expansions() {
+    for input in INPUTS {
+        let automaton = load(input);
+
+        let astar = solve(&automaton, &SolveOptions::default()).unwrap();
+        let pruned = solve(
+            &automaton,
+            &SolveOptions {
+                solver: Solver::Pruned,
+                ..SolveOptions::default()
+            },
+        )
+        .unwrap();
+
+        let pruning = pruned.stats.pruning.unwrap();
+
+        assert_eq!(pruned.length(), astar.length(), "{}", input);
+        assert!(
+            pruned.stats.states_expanded <= astar.stats.states_expanded,
+            "{}",
+            input
+        );
+        assert!(pruning.live_states > 0, "{}", input);
+        assert!(pruning.pruned_states() > 0, "{}", input);
+        assert_eq!(astar.stats.pruning, None, "{}", input);
+    }
+}
+
+#[test]
+fn pruning_agrees_with_astar_on_a_board_without_a_path() {
+    for solver in [Solver::AStar, Solver::Pruned] {
+        let outcome = find_path_with_observer(
+            unreachable_finish(),
+            solver,
+            SearchLimits::default(),
+            &Interrupt::new(),
+            &mut SilentObserver,
+        );
+
+        assert!(
+            matches!(outcome, SearchOutcome::NoPath),
+            "{:?}: {:?}",
+            solver,
+            outcome
+        );
+    }
+}
+
+#[test]
+fn anytime_reports_shorter_paths_until_optimum() {
+    for input in INPUTS {
+        let automaton = load(input);
+
+        let optimum = find_path(automaton.clone(), Solver::Bfs).unwrap();
+
+        let mut observer = Improvements::default();
+        let path = find_path_with_observer(
+            automaton.clone(),
+            Solver::Anytime,
+            SearchLimits::default(),
+            &Interrupt::new(),
+            &mut observer,
+        )
+        .path()
+        .unwrap();
+
+        assert!(observer
+            .0
+            .iter()
+            .all(|path| verify_result(&automaton, path)));
+
+        let improvements: Vec<usize> = observer.0.iter().map(|path| path.len()).collect();
+
+        assert!(improvements.windows(2).all(|pair| pair[1] < pair[0]));
+        assert_eq!(improvements.last(), Some(&path.len()));
+        assert_eq!(path.len(), optimum.len(), "{}", input);
+    }
+}
+
+#[test]
+fn beam_returns_verified_path_no_shorter_than_optimum() {
+    for input in INPUTS {
+        let automaton = load(input);
+
+        let optimum = find_path(automaton.clone(), Solver::Bfs).unwrap();
+
+        let solver = Solver::Beam(BeamOptions {
+            width: 20,
+            region_size: 2,
+        });
+
+        let path = find_path(automaton.clone(), solver)
+            .unwrap_or_else(|| panic!("No path through {}", input));
+
+        assert!(verify_result(&automaton, &path));
+        assert!(path.len() >= optimum.len(), "{}", input);
+    }
+}
+
+#[test]
+fn narrow_beam_returns_no_path_after_a_dead_end() {
+    // Keeping a single state walks the particle into a cell that dies out
+    let solver = Solver::Beam(BeamOptions {
+        width: 1,
+        region_size: 1,
+    });
+
+    let outcome = find_path_with_observer(
+        load("second_challenge"),
+        solver,
+        SearchLimits::default(),
+        &Interrupt::new(),
+        &mut SilentObserver,
+    );
+
+    assert!(matches!(outcome, SearchOutcome::NoPath), "{:?}", outcome);
+}
+
+#[test]
+fn parallel_returns_the_same_path_for_any_number_of_threads() {
+    for input in INPUTS {
+        let automaton = load(input);
+
+        let single_thread = find_path(automaton.clone(), Solver::Bfs).unwrap();
+
+        for threads in [2, 3, 8] {
+            let path = find_path(automaton.clone(), Solver::Parallel(threads)).unwrap();
+
+            assert_eq!(path, single_thread, "{} with {} threads", input, threads);
+        }
+    }
+}
+
+#[test]
+fn every_solver_stops_at_the_generation_limit() {
+    let automaton = load("first_challenge");
+    let optimum = find_path(automaton.clone(), Solver::Bfs).unwrap();
+
+    let limits = SearchLimits {
+        max_generation: Some(optimum.len() as u32 - 1),
+        ..SearchLimits::default()
+    };
+
+    for solver in [
+        "astar",
+        "anytime",
+        "beam",
+        "bfs",
+        "bidirectional",
+        "original",
+        "pruned",
+    ] {
+        let outcome = find_path_with_observer(
+            automaton.clone(),
+            solver.parse().unwrap(),
+            limits,
+            &Interrupt::new(),
+            &mut SilentObserver,
+        );
+
+        match outcome {
+            SearchOutcome::LimitExceeded {
+                which: Limit::MaxGeneration,
+                partial: Some(partial),
+            } => assert!(partial.generation < optimum.len() as u32, "{}", solver),
+            outcome => panic!("{}: {:?}", solver, outcome),
+        }
+    }
+}
+
+#[test]
+fn generation_limit_is_only_reported_when_one_was_set() {
+    let automaton = unreachable_finish();
+
+    for solver in ["beam", "bfs", "bidirectional"] {
+        let unlimited = find_path_with_observer(
+            automaton.clone(),
+            solver.parse().unwrap(),
+            SearchLimits::default(),
+            &Interrupt::new(),
+            &mut SilentObserver,
+        );
+
+        assert!(
+            matches!(unlimited, SearchOutcome::NoPath),
+            "{}: {:?}",
+            solver,
+            unlimited
+        );
+
+        let limited = find_path_with_observer(
+            automaton.clone(),
+            solver.parse().unwrap(),
+            SearchLimits {
+                max_generation: Some(20),
+                ..SearchLimits::default()
+            },
+            &Interrupt::new(),
+            &mut SilentObserver,
+        );
+
+        assert!(
+            matches!(
+                limited,
+                SearchOutcome::LimitExceeded {
+                    which: Limit::MaxGeneration,
+                    ..
+                }
+            ),
+            "{}: {:?}",
+            solver,
+            limited
+        );
+    }
+}
+
+#[test]
+fn original_only_reports_verified_improvements() {
+    // The particle starts on a cell that is alive in the sample
+    let mut automaton = load("sample");
+    automaton.starting_point = (3, 4);
+
+    let mut observer = Improvements::default();
+
+    let outcome = find_path_with_observer(
+        automaton,
+        Solver::Original(DfsOptions::default()),
+        SearchLimits::default(),
+        &Interrupt::new(),
+        &mut observer,
+    );
+
+    assert!(observer.0.is_empty(), "{:?}", observer.0);
+    assert!(matches!(outcome, SearchOutcome::NoPath), "{:?}", outcome);
+}
+
+#[test]
+fn original_returns_the_same_path_for_the_same_seed() {
+    let automaton = load("first_challenge");
+
+    for seed in [0, 1, 7] {
+        let options = DfsOptions { seed, restarts: 3 };
+
+        let first = find_path(automaton.clone(), Solver::Original(options)).unwrap();
+        let second = find_path(automaton.clone(), Solver::Original(options)).unwrap();
+
+        assert_eq!(first, second, "seed {}", seed);
+
+        let single = find_path(
+            automaton.clone(),
+            Solver::Original(DfsOptions { seed, restarts: 1 }),
+        )
+        .unwrap();
+
+        assert!(first.len() <= single.len(), "seed {}", seed);
+    }
+}
+
+#[test]
+fn shortened_paths_stay_valid_and_reach_the_optimum_with_a_full_window() {
+    for input in INPUTS {
+        let automaton = load(input);
+        let optimum = find_path(automaton.clone(), Solver::Bfs).unwrap();
+        let path = find_path(automaton.clone(), "original".parse().unwrap()).unwrap();
+
+        for window in [4, 16, path.len()] {
+            let shortened = shorten_path(&automaton, &path, window);
+
+            assert!(verify_result(&automaton, &shortened), "{}", input);
+            assert!(shortened.len() <= path.len(), "{}", input);
+            assert_eq!(shortened.first(), path.first(), "{}", input);
+            assert_eq!(shortened.last(), path.last(), "{}", input);
+
+            if window == path.len() {
+                assert_eq!(shortened.len(), optimum.len(), "{}", input);
+            }
+        }
+    }
+}
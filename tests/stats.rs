@@ -0,0 +1,300 @@
+use std::collections::HashSet;
+
+use stone_challenge::automaton::{Automaton, CellState};
+use stone_challenge::path_finder::shared::get_possible_moves;
+use stone_challenge::path_finder::Solver;
+use stone_challenge::render::RenderOptions;
+use stone_challenge::stats::difficulty::{rate, BOTTLENECK_CELLS};
+use stone_challenge::stats::generations::{write_report, Generations, ReportFormat};
+use stone_challenge::stats::heatmap::Heatmap;
+use stone_challenge::{solve, SolveOptions};
+
+fn load(input: &str) -> Automaton {
+    Automaton::from_file(&format!("inputs/{}.json", input)).unwrap()
+}
+
+#[test]
+fn heatmap_counts_alive_and_free_generations_of_every_cell() {
+    let automaton = load("sample");
+    let heatmap = Heatmap::new(&automaton, 12);
+
+    let generations: Vec<Automaton> = (0..12)
+        .scan(automaton.clone(), |current, _| {
+            let board = current.clone();
+            *current = current.iterate();
+            Some(board)
+        })
+        .collect();
+
+    for i in 0..heatmap.height {
+        for j in 0..heatmap.width {
+            let alive: Vec<bool> = generations
+                .iter()
+                .map(|board| board.matrix[i][j] == CellState::Alive)
+                .collect();
+
+            let free_generations = || {
+                generations
+                    .iter()
+                    .filter(|board| board.matrix[i][j] != CellState::Alive)
+                    .map(|board| board.generation)
+            };
+
+            let alive_count = alive.iter().filter(|alive| **alive).count() as u32;
+
+            assert_eq!(heatmap.alive_count((i, j)), alive_count);
+            assert_eq!(heatmap.occupancy((i, j)), alive_count as f64 / 12.0);
+            assert_eq!(heatmap.first_free((i, j)), free_generations().next());
+            assert_eq!(heatmap.last_free((i, j)), free_generations().next_back());
+        }
+    }
+
+    assert_eq!(heatmap.alive_count(automaton.starting_point), 0);
+    assert_eq!(heatmap.first_free(automaton.finishing_point), Some(1));
+    assert_eq!(heatmap.last_free(automaton.finishing_point), Some(12));
+}
+
+#[test]
+fn heatmap_exports_matrices() {
+    let automaton = load("first_challenge");
+    let heatmap = Heatmap::new(&automaton, 30);
+
+    let mut csv: Vec<u8> = vec![];
+    heatmap.write_csv(&mut csv).unwrap();
+    let csv = String::from_utf8(csv).unwrap();
+    let rows: Vec<Vec<u32>> = csv
+        .lines()
+        .map(|line| {
+            line.split(',')
+                .map(|count| count.parse().unwrap())
+                .collect()
+        })
+        .collect();
+
+    assert_eq!(rows.len(), heatmap.height);
+    assert_eq!(rows[2][3], heatmap.alive_count((2, 3)));
+
+    let json = heatmap.to_json();
+
+    assert_eq!(json["generations"], 30);
+    assert_eq!(json["alive_counts"][2][3], heatmap.alive_count((2, 3)));
+    assert_eq!(
+        json["alive_counts"][0].as_array().unwrap().len(),
+        heatmap.width
+    );
+    assert_eq!(json["first_free"][0][0], 1);
+
+    let image = heatmap.to_image(
+        &automaton,
+        &RenderOptions {
+            cell_size: 2,
+            ..RenderOptions::default()
+        },
+    );
+
+    assert_eq!(
+        (image.width, image.height),
+        (heatmap.width * 2, heatmap.height * 2)
+    );
+}
+
+/// Takes every byte, but fails to flush them.
+struct UnflushableWriter;
+
+impl std::io::Write for UnflushableWriter {
+    fn write(&mut self, buffer: &[u8]) -> std::io::Result<usize> {
+        Ok(buffer.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Err(std::io::Error::other("disk full"))
+    }
+}
+
+#[test]
+fn heatmap_reports_errors_when_flushing() {
+    let heatmap = Heatmap::new(&load("sample"), 12);
+
+    assert!(heatmap.write_csv(UnflushableWriter).is_err());
+}
+
+#[test]
+fn generation_stats_match_the_boards() {
+    let automaton = load("sample");
+    let mut previous: Option<Automaton> = None;
+    let mut board = automaton.clone();
+
+    for stats in Generations::new(&automaton, 15) {
+        let alive: Vec<(usize, usize)> = (0..board.matrix.len())
+            .flat_map(|i| (0..board.matrix[0].len()).map(move |j| (i, j)))
+            .filter(|(i, j)| board.matrix[*i][*j] == CellState::Alive)
+            .collect();
+        let was_alive = |(i, j): (usize, usize)| {
+            previous
+                .as_ref()
+                .map(|previous| previous.matrix[i][j] == CellState::Alive)
+        };
+
+        assert_eq!(stats.generation, board.generation);
+        assert_eq!(stats.population, alive.len());
+        assert_eq!(stats.density, alive.len() as f64 / 90.0);
+
+        if let Some(previous) = &previous {
+            let births = alive.iter().filter(|cell| was_alive(**cell) == Some(false));
+            let survivors = alive.len() - births.count();
+            let previous_population = previous
+                .matrix
+                .iter()
+                .flatten()
+                .filter(|cell| **cell == CellState::Alive)
+                .count();
+
+            assert_eq!(stats.births, Some(alive.len() - survivors));
+            assert_eq!(stats.deaths, Some(previous_population - survivors));
+        } else {
+            assert_eq!((stats.births, stats.deaths), (None, None));
+        }
+
+        let bounds = stats.bounding_box.unwrap();
+
+        assert_eq!(bounds.top, alive.iter().map(|(i, _)| *i).min().unwrap());
+        assert_eq!(bounds.bottom, alive.iter().map(|(i, _)| *i).max().unwrap());
+        assert_eq!(bounds.left, alive.iter().map(|(_, j)| *j).min().unwrap());
+        assert_eq!(bounds.right, alive.iter().map(|(_, j)| *j).max().unwrap());
+
+        let next = board.iterate();
+        previous = Some(std::mem::replace(&mut board, next));
+    }
+}
+
+#[test]
+fn generation_stats_flag_walled_in_start_and_finish() {
+    let mut automaton = load("sample");
+    let (i, j) = automaton.starting_point;
+
+    automaton.matrix[i][j + 1] = CellState::Alive;
+    automaton.matrix[i + 1][j] = CellState::Alive;
+
+    let stats = Generations::new(&automaton, 1).next().unwrap();
+
+    assert!(stats.start_blocked);
+    assert!(!stats.finish_blocked);
+}
+
+#[test]
+fn generation_report_writes_csv_and_json() {
+    let automaton = load("first_challenge");
+
+    let mut csv: Vec<u8> = vec![];
+    write_report(
+        Generations::new(&automaton, 10),
+        ReportFormat::Csv,
+        &mut csv,
+    )
+    .unwrap();
+    let csv = String::from_utf8(csv).unwrap();
+    let lines: Vec<&str> = csv.lines().collect();
+
+    assert_eq!(lines.len(), 11);
+    assert!(lines[0].starts_with("generation,population,density,births,deaths"));
+    assert!(lines[1].starts_with("1,"));
+    assert!(lines.iter().all(|line| line.split(',').count() == 8));
+
+    let mut json: Vec<u8> = vec![];
+    write_report(
+        Generations::new(&automaton, 10),
+        ReportFormat::Json,
+        &mut json,
+    )
+    .unwrap();
+    let json: serde_json::Value = serde_json::from_slice(&json).unwrap();
+    let generations = json.as_array().unwrap();
+
+    assert_eq!(generations.len(), 10);
+    assert_eq!(generations[0]["generation"], 1);
+    assert!(generations[0]["births"].is_null());
+    assert_eq!(generations[9]["generation"], 10);
+
+    let mut empty: Vec<u8> = vec![];
+    write_report(
+        Generations::new(&automaton, 0),
+        ReportFormat::Json,
+        &mut empty,
+    )
+    .unwrap();
+
+    assert_eq!(
+        serde_json::from_slice::<serde_json::Value>(&empty).unwrap(),
+        serde_json::json!([])
+    );
+}
+
+#[test]
+fn difficulty_follows_the_reachable_cells_to_the_optimal_path() {
+    for input in ["sample", "first_challenge"] {
+        let automaton = load(input);
+        let difficulty = rate(&automaton, 100);
+
+        let optimal = solve(
+            &automaton,
+            &SolveOptions {
+                solver: Solver::Bfs,
+                ..SolveOptions::default()
+            },
+        )
+        .unwrap();
+        let optimal_moves = optimal.length().unwrap() as u32 - 1;
+
+        assert_eq!(difficulty.optimal_moves, Some(optimal_moves));
+        assert_eq!(
+            difficulty.detour,
+            Some(optimal_moves as f64 / difficulty.manhattan_distance as f64)
+        );
+
+        let mut board = automaton.clone();
+        let mut reachable = HashSet::from([automaton.starting_point]);
+        let mut counts = vec![1];
+
+        for _ in 0..optimal_moves {
+            board = board.iterate();
+            reachable = reachable
+                .iter()
+                .flat_map(|position| get_possible_moves(&board.matrix, *position))
+                .collect();
+            counts.push(reachable.len());
+        }
+
+        assert_eq!(difficulty.reachable_states, counts);
+
+        let bottlenecks: Vec<u32> = (1..counts.len())
+            .filter(|index| {
+                counts[*index] < counts[index - 1] && counts[*index] <= BOTTLENECK_CELLS
+            })
+            .map(|index| index as u32 + 1)
+            .collect();
+
+        assert_eq!(
+            difficulty
+                .bottlenecks
+                .iter()
+                .map(|bottleneck| bottleneck.generation)
+                .collect::<Vec<_>>(),
+            bottlenecks
+        );
+        assert_eq!(
+            difficulty.score,
+            difficulty
+                .detour
+                .map(|detour| detour * (1 + bottlenecks.len()) as f64)
+        );
+    }
+}
+
+#[test]
+fn difficulty_without_a_path_within_the_horizon_has_no_score() {
+    let difficulty = rate(&load("first_challenge"), 10);
+
+    assert_eq!(difficulty.optimal_moves, None);
+    assert_eq!(difficulty.score, None);
+    assert_eq!(difficulty.reachable_states.len(), 10);
+}
@@ -0,0 +1,188 @@
+use std::collections::HashSet;
+
+use proptest::prelude::*;
+use stone_challenge::automaton::{Automaton, CellState, Position};
+use stone_challenge::sparse_automaton::SparseAutomaton;
+
+/// Integer matrices of up to 12 by 12 cells, alive with the given chance,
+/// with a start and a finish anywhere on the board.
+fn boards(density: f64) -> impl Strategy<Value = Vec<Vec<i32>>> {
+    (1usize..=12, 1usize..=12)
+        .prop_filter("room for the start and finish", |(height, width)| {
+            height * width >= 2
+        })
+        .prop_flat_map(move |(height, width)| {
+            let cells = height * width;
+
+            (
+                Just(width),
+                prop::collection::vec(prop::bool::weighted(density), cells),
+                0..cells,
+                1..cells,
+            )
+        })
+        .prop_map(|(width, alive, start, offset)| {
+            let finish = (start + offset) % alive.len();
+
+            let mut cells: Vec<i32> = alive.into_iter().map(i32::from).collect();
+            cells[start] = 2;
+            cells[finish] = 3;
+
+            cells.chunks(width).map(|row| row.to_vec()).collect()
+        })
+}
+
+fn alive_cells(automaton: &Automaton) -> HashSet<Position> {
+    let mut cells = HashSet::new();
+
+    for (i, row) in automaton.matrix.iter().enumerate() {
+        for (j, state) in row.iter().enumerate() {
+            if *state == CellState::Alive {
+                cells.insert((i, j));
+            }
+        }
+    }
+
+    cells
+}
+
+/// Counts the alive cells around `(i, j)` by trying every offset.
+fn brute_force_neighbours(matrix: &[Vec<CellState>], (i, j): Position) -> i32 {
+    let mut count = 0;
+
+    for di in -1isize..=1 {
+        for dj in -1isize..=1 {
+            if di == 0 && dj == 0 {
+                continue;
+            }
+
+            let (Some(x), Some(y)) = (i.checked_add_signed(di), j.checked_add_signed(dj)) else {
+                continue;
+            };
+
+            if matrix.get(x).and_then(|row| row.get(y)) == Some(&CellState::Alive) {
+                count += 1;
+            }
+        }
+    }
+
+    count
+}
+
+proptest! {
+    #[test]
+    fn dense_and_sparse_steppers_agree(matrix in boards(0.4), generations in 1usize..10) {
+        let mut dense = Automaton::from_integer_matrix(matrix.clone());
+        let mut sparse = SparseAutomaton::from_integer_matrix(matrix);
+
+        prop_assert_eq!(alive_cells(&dense), sparse.live_cells.clone());
+
+        for _ in 0..generations {
+            dense = dense.iterate();
+            sparse = sparse.iterate();
+
+            prop_assert_eq!(dense.generation, sparse.generation);
+            prop_assert_eq!(alive_cells(&dense), sparse.live_cells.clone());
+        }
+    }
+
+    #[test]
+    fn parallel_stepper_agrees_with_the_sequential_one(
+        matrix in boards(0.4),
+        threads in 1usize..6,
+        generations in 1usize..6,
+    ) {
+        let mut sequential = Automaton::from_integer_matrix(matrix);
+        let mut parallel = sequential.clone();
+
+        for _ in 0..generations {
+            sequential = sequential.iterate();
+            parallel = parallel.iterate_parallel(threads);
+
+            prop_assert_eq!(&parallel.matrix, &sequential.matrix);
+        }
+    }
+
+    #[test]
+    fn integer_matrices_round_trip(matrix in boards(0.5)) {
+        let automaton = Automaton::from_integer_matrix(matrix.clone());
+
+        prop_assert_eq!(automaton.to_integer_matrix(), matrix);
+    }
+
+    #[test]
+    fn start_and_finish_never_change(matrix in boards(0.6), generations in 1usize..15) {
+        let mut automaton = Automaton::from_integer_matrix(matrix);
+        let (start_i, start_j) = automaton.starting_point;
+        let (finish_i, finish_j) = automaton.finishing_point;
+
+        for _ in 0..generations {
+            automaton = automaton.iterate();
+
+            prop_assert_eq!(automaton.matrix[start_i][start_j], CellState::Start);
+            prop_assert_eq!(automaton.matrix[finish_i][finish_j], CellState::Finish);
+
+            let special = automaton
+                .matrix
+                .iter()
+                .flatten()
+                .filter(|state| matches!(state, CellState::Start | CellState::Finish))
+                .count();
+
+            prop_assert_eq!(special, 2);
+        }
+    }
+
+    #[test]
+    fn neighbour_counts_match_brute_force(matrix in boards(0.5)) {
+        let automaton = Automaton::from_integer_matrix(matrix);
+
+        // Every cell, so the edges and corners of every shape are covered
+        for i in 0..automaton.matrix.len() {
+            for j in 0..automaton.matrix[0].len() {
+                prop_assert_eq!(
+                    automaton.get_number_of_alive_neighbours((i, j)),
+                    brute_force_neighbours(&automaton.matrix, (i, j)),
+                    "cell ({}, {})",
+                    i,
+                    j
+                );
+            }
+        }
+    }
+}
+
+#[test]
+fn steppers_agree_on_the_challenge_inputs() {
+    for input in ["sample", "first_challenge", "second_challenge"] {
+        let path = format!("inputs/{}.json", input);
+        let mut dense = Automaton::from_file(&path).unwrap();
+        let mut sparse = SparseAutomaton::from_file(&path).unwrap();
+
+        for _ in 0..20 {
+            dense = dense.iterate();
+            sparse = sparse.iterate();
+
+            assert_eq!(alive_cells(&dense), sparse.live_cells, "{}", input);
+        }
+    }
+}
+
+#[test]
+fn fully_alive_board_counts_every_neighbour() {
+    let mut matrix = vec![vec![1; 5]; 4];
+    matrix[0][0] = 2;
+    matrix[3][4] = 3;
+
+    let automaton = Automaton::from_integer_matrix(matrix);
+
+    // Corners, next to the start and finish which are never alive
+    assert_eq!(automaton.get_number_of_alive_neighbours((0, 4)), 3);
+    assert_eq!(automaton.get_number_of_alive_neighbours((3, 0)), 3);
+    assert_eq!(automaton.get_number_of_alive_neighbours((1, 1)), 7);
+    // Edges
+    assert_eq!(automaton.get_number_of_alive_neighbours((0, 2)), 5);
+    assert_eq!(automaton.get_number_of_alive_neighbours((2, 4)), 4);
+    // Inside
+    assert_eq!(automaton.get_number_of_alive_neighbours((2, 2)), 8);
+}